@@ -0,0 +1,259 @@
+//! Derive macro `TomlView` for tomloper, generate typed accessors from toml layout.
+//!
+//! Each field declare the path where to read it, and optional default value:
+//! ```rust,ignore
+//! #[derive(TomlView)]
+//! struct Server<'a> {
+//!     /// Listening address.
+//!     #[toml(path = "host.ip", default = "127.0.0.1")]
+//!     ip: &'a str,
+//!     #[toml(path = "host.port", default = 8080)]
+//!     port: i64,
+//!     #[toml(path = "service.0.name")]
+//!     first: Option<String>,
+//! }
+//! ```
+//! It generates `ServerView`, a zero-copy view over borrowed `toml::Value`,
+//! which has a method for each field that resolve the path on demand,
+//! and `Server::view(&value)` to create it.
+//! The lifetime of the view is named `'__tomloper`, not to clash with the one of the struct.
+//! The path is split into typed segments at compile time, as `toml_path!` does.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, GenericArgument,
+    LitStr, PathArguments, Type};
+
+#[proc_macro_derive(TomlView, attributes(toml))]
+pub fn derive_toml_view(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(output) => output.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Supported field type, and how to extract it with pipe operator.
+enum Kind {
+    Integer,
+    Float,
+    Bool,
+    Str,
+    String,
+    Ptr,
+    Option(Box<Kind>),
+}
+
+/// Parsed field of the layout struct.
+struct ViewField {
+    name: syn::Ident,
+    kind: Kind,
+    path: String,
+    default: Option<Expr>,
+    docs: Vec<syn::Attribute>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "TomlView expect struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "TomlView can only derive for struct")),
+    };
+    if input.generics.type_params().next().is_some()
+        || input.generics.const_params().next().is_some()
+        || input.generics.lifetimes().count() > 1 {
+        return Err(syn::Error::new_spanned(&input.generics, "TomlView support at most one lifetime parameter"));
+    }
+
+    let mut view_fields = Vec::new();
+    for field in fields {
+        view_fields.push(parse_field(field)?);
+    }
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let view = format_ident!("{}View", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let target = if input.generics.lifetimes().next().is_some() {
+        quote! { #name<'__tomloper> }
+    } else {
+        quote! { #name }
+    };
+
+    let methods = view_fields.iter().map(expand_method).collect::<syn::Result<Vec<_>>>()?;
+    let names = view_fields.iter().map(|f| &f.name);
+    let view_doc = format!("Zero-copy view of [`{}`] over a borrowed `toml::Value`.", name);
+
+    Ok(quote! {
+        #[doc = #view_doc]
+        #[derive(Copy, Clone)]
+        #vis struct #view<'__tomloper> {
+            root: ::tomloper::TomlPtr<'__tomloper>,
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Create view over the toml tree, paths are resolved from this root.
+            pub fn view<'__tomloper>(v: &'__tomloper ::tomloper::toml::Value) -> #view<'__tomloper> {
+                #view { root: ::tomloper::TomlPtr::path(v) }
+            }
+        }
+
+        impl<'__tomloper> #view<'__tomloper> {
+            /// Create view from toml pointer, which may point to sub-node.
+            pub fn new(root: ::tomloper::TomlPtr<'__tomloper>) -> Self {
+                Self { root }
+            }
+
+            /// Read all fields and build the layout struct.
+            pub fn load(&self) -> #target {
+                #name { #( #names: self.#names(), )* }
+            }
+
+            #( #methods )*
+        }
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<ViewField> {
+    let name = field.ident.clone().unwrap();
+    let kind = parse_kind(&field.ty)?;
+    let mut path = name.to_string();
+    let mut default = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("toml") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("path") {
+                let lit: LitStr = meta.value()?.parse()?;
+                path = lit.value();
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expect `path` or `default` in toml attribute"))
+            }
+        })?;
+    }
+    if default.is_some() && matches!(kind, Kind::Ptr | Kind::Option(_)) {
+        return Err(syn::Error::new_spanned(&field.ty, "default is not supported for TomlPtr or Option field"));
+    }
+    let docs = field.attrs.iter().filter(|a| a.path().is_ident("doc")).cloned().collect();
+    Ok(ViewField { name, kind, path, default, docs })
+}
+
+fn parse_kind(ty: &Type) -> syn::Result<Kind> {
+    let unsupported = || syn::Error::new_spanned(ty,
+        "unsupported field type for TomlView, expect i64, f64, bool, &str, String, TomlPtr or Option of them");
+    match ty {
+        Type::Reference(r) => match &*r.elem {
+            Type::Path(p) if p.path.is_ident("str") => Ok(Kind::Str),
+            _ => Err(unsupported()),
+        },
+        Type::Path(p) => {
+            let last = p.path.segments.last().ok_or_else(unsupported)?;
+            match last.ident.to_string().as_str() {
+                "i64" => Ok(Kind::Integer),
+                "f64" => Ok(Kind::Float),
+                "bool" => Ok(Kind::Bool),
+                "String" => Ok(Kind::String),
+                "TomlPtr" => Ok(Kind::Ptr),
+                "Option" => {
+                    let inner = match &last.arguments {
+                        PathArguments::AngleBracketed(args) => match args.args.first() {
+                            Some(GenericArgument::Type(inner)) => parse_kind(inner)?,
+                            _ => return Err(unsupported()),
+                        },
+                        _ => return Err(unsupported()),
+                    };
+                    match inner {
+                        Kind::Ptr | Kind::Option(_) => Err(unsupported()),
+                        inner => Ok(Kind::Option(Box::new(inner))),
+                    }
+                },
+                _ => Err(unsupported()),
+            }
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+/// Split path string into typed segments, numeric segment is left to be decided
+/// by the node, as key for table or index for array, the same as `pathto()`.
+fn expand_segments(path: &str) -> TokenStream2 {
+    let segments = path
+        .split(['/', '.'])
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<isize>() {
+            Ok(_) => quote! { ::tomloper::Segment::Auto(#s) },
+            Err(_) => quote! { ::tomloper::Segment::Key(#s) },
+        });
+    quote! {
+        ::tomloper::StaticPath::new(&[ #( #segments, )* ])
+    }
+}
+
+fn expand_method(field: &ViewField) -> syn::Result<TokenStream2> {
+    let name = &field.name;
+    let docs = &field.docs;
+    let segments = expand_segments(&field.path);
+    let path_doc = match &field.default {
+        Some(d) => format!("Read from path `{}`, default `{}`.", field.path, quote!(#d)),
+        None => format!("Read from path `{}`.", field.path),
+    };
+    let sep = if docs.is_empty() { quote!() } else { quote!(#[doc = ""]) };
+
+    let (ty, body) = match &field.kind {
+        Kind::Option(inner) => {
+            let (ty, getter) = match **inner {
+                Kind::Integer => (quote!(i64), quote!(v.as_integer())),
+                Kind::Float => (quote!(f64), quote!(v.as_float())),
+                Kind::Bool => (quote!(bool), quote!(v.as_bool())),
+                Kind::Str => (quote!(&'__tomloper str), quote!(v.as_str())),
+                Kind::String => (quote!(::std::string::String), quote!(v.as_str().map(::std::string::String::from))),
+                Kind::Ptr | Kind::Option(_) => unreachable!(),
+            };
+            (quote!(::std::option::Option<#ty>), quote! {
+                let node = self.root / #segments;
+                node.unpath().and_then(|v| #getter)
+            })
+        },
+        Kind::Ptr => (quote!(::tomloper::TomlPtr<'__tomloper>), quote! { self.root / #segments }),
+        kind => {
+            let (ty, default) = match kind {
+                Kind::Integer => (quote!(i64), quote!(0)),
+                Kind::Float => (quote!(f64), quote!(0.0)),
+                Kind::Bool => (quote!(bool), quote!(false)),
+                Kind::Str => (quote!(&'static str), quote!("")),
+                Kind::String => (quote!(::std::string::String), quote!(::std::string::String::new())),
+                _ => unreachable!(),
+            };
+            let default = match (&field.default, kind) {
+                (Some(d), Kind::String) => quote!(::std::string::ToString::to_string(&#d)),
+                (Some(d), _) => quote!(#d),
+                (None, _) => default,
+            };
+            let ret = match kind {
+                Kind::Str => quote!(&'__tomloper str),
+                _ => ty.clone(),
+            };
+            (ret, quote! {
+                let default: #ty = #default;
+                self.root / #segments | default
+            })
+        },
+    };
+
+    Ok(quote! {
+        #( #docs )*
+        #sep
+        #[doc = #path_doc]
+        pub fn #name(&self) -> #ty {
+            #body
+        }
+    })
+}
@@ -0,0 +1,78 @@
+/*
+ * C bindings of tomloper path query, link with libtomloper_ffi.
+ *
+ * Path is split on slash(/) or dot(.), numeric segment is array index
+ * and negative one count from the end, eg. "host/port" or "service.-1.name".
+ *
+ * Ownership:
+ *   - TomloperDoc from tomloper_parse() is released by tomloper_free().
+ *   - char * from tomloper_get_str() and tomloper_serialize() is owned by
+ *     caller, released by tomloper_string_free().
+ *   - const char * argument is only borrowed during the call.
+ * Free functions accept NULL. Getters return the default value when the path
+ * is invalid, type mismatch or any argument is NULL. Other functions return
+ * TOMLOPER_OK or negative error code.
+ */
+
+#ifndef TOMLOPER_H
+#define TOMLOPER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define TOMLOPER_OK 0
+/* Required pointer argument is NULL. */
+#define TOMLOPER_NULL_ARG (-1)
+/* String argument is not valid UTF-8. */
+#define TOMLOPER_BAD_UTF8 (-2)
+/* Source string is not valid toml. */
+#define TOMLOPER_PARSE_ERROR (-3)
+/* Path is not found, and cannot be added as new key of its parent table. */
+#define TOMLOPER_NOT_FOUND (-4)
+/* Existing node has different type from the value to set. */
+#define TOMLOPER_TYPE_MISMATCH (-5)
+/* Document cannot be written as toml text. */
+#define TOMLOPER_SERIALIZE_ERROR (-6)
+
+/* Opaque handle of parsed toml document. */
+typedef struct TomloperDoc TomloperDoc;
+
+/* Parse toml string, store new handle to *out on success, or NULL on error. */
+int tomloper_parse(const char *src, TomloperDoc **out);
+
+/* Release document handle. */
+void tomloper_free(TomloperDoc *doc);
+
+/* Release string returned by this library. */
+void tomloper_string_free(char *s);
+
+/* Get string value or copy of dflt, caller free the result.
+ * Return NULL only if dflt is NULL and the value is not found. */
+char *tomloper_get_str(const TomloperDoc *doc, const char *path, const char *dflt);
+
+/* Get scalar value or dflt. */
+int64_t tomloper_get_int(const TomloperDoc *doc, const char *path, int64_t dflt);
+double tomloper_get_float(const TomloperDoc *doc, const char *path, double dflt);
+bool tomloper_get_bool(const TomloperDoc *doc, const char *path, bool dflt);
+
+/* Set value to existing node of the same type, or add new key to parent table. */
+int tomloper_set_str(TomloperDoc *doc, const char *path, const char *val);
+int tomloper_set_int(TomloperDoc *doc, const char *path, int64_t val);
+int tomloper_set_float(TomloperDoc *doc, const char *path, double val);
+int tomloper_set_bool(TomloperDoc *doc, const char *path, bool val);
+
+/* Write document as toml string to *out, caller free it by tomloper_string_free(). */
+int tomloper_serialize(const TomloperDoc *doc, char **out);
+
+/* Static message of error code, never NULL and need not free. */
+const char *tomloper_strerror(int code);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TOMLOPER_H */
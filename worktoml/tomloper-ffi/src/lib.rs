@@ -0,0 +1,270 @@
+//! C bindings of tomloper path query, built as `cdylib` with the header `include/tomloper.h`.
+//!
+//! Ownership is simple and explicit:
+//! * `tomloper_parse()` create a `TomloperDoc` handle, release it by `tomloper_free()`.
+//! * `char *` returned by `tomloper_get_str()` or `tomloper_serialize()` is owned by caller,
+//!   release it by `tomloper_string_free()`.
+//! * `const char *` argument is only borrowed during the call.
+//!
+//! All the free functions accept null pointer and do nothing.
+//! Getters mirror the `|` operator, return the default value if the path is invalid
+//! or type mismatch, also when any pointer argument is null.
+//! Other functions return `TOMLOPER_OK` (0) or negative error code, never crash on null.
+//!
+//! ```c
+//! TomloperDoc *doc = NULL;
+//! if (tomloper_parse("[host]\nport = 8080\n", &doc) == TOMLOPER_OK) {
+//!     int64_t port = tomloper_get_int(doc, "host/port", 0);
+//!     tomloper_set_str(doc, "host.ip", "127.0.0.1");
+//!     char *text = NULL;
+//!     tomloper_serialize(doc, &text);
+//!     tomloper_string_free(text);
+//! }
+//! tomloper_free(doc);
+//! ```
+
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::mem::discriminant;
+use std::ptr;
+
+use toml::Value;
+use tomloper::{PathOperator, TomlPtr};
+
+/// Success.
+pub const TOMLOPER_OK: c_int = 0;
+/// Required pointer argument is null.
+pub const TOMLOPER_NULL_ARG: c_int = -1;
+/// String argument is not valid UTF-8.
+pub const TOMLOPER_BAD_UTF8: c_int = -2;
+/// Source string is not valid toml.
+pub const TOMLOPER_PARSE_ERROR: c_int = -3;
+/// Path is not found, and cannot be added as new key of its parent table.
+pub const TOMLOPER_NOT_FOUND: c_int = -4;
+/// Existing node has different type from the value to set.
+pub const TOMLOPER_TYPE_MISMATCH: c_int = -5;
+/// Document cannot be written as toml text.
+pub const TOMLOPER_SERIALIZE_ERROR: c_int = -6;
+
+/// Opaque handle of parsed toml document.
+pub struct TomloperDoc {
+    root: Value,
+}
+
+/// Borrow C string as `&str` for the call.
+unsafe fn borrow_str<'a>(s: *const c_char) -> Result<&'a str, c_int> {
+    if s.is_null() {
+        return Err(TOMLOPER_NULL_ARG);
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| TOMLOPER_BAD_UTF8)
+}
+
+/// Resolve path in the document, `None` if any argument is bad.
+unsafe fn lookup<'a>(doc: *const TomloperDoc, path: *const c_char) -> Option<TomlPtr<'a>> {
+    let doc = doc.as_ref()?;
+    let path = borrow_str(path).ok()?;
+    Some(doc.root.pathto(path))
+}
+
+/// Set value to the path, as `<<` if node exists, or push new key into parent table.
+unsafe fn set_value(doc: *mut TomloperDoc, path: *const c_char, val: Value) -> c_int {
+    let Some(doc) = doc.as_mut() else {
+        return TOMLOPER_NULL_ARG;
+    };
+    let path = match borrow_str(path) {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+
+    let same_type = doc.root.pathto(path).map(|old| discriminant(old) == discriminant(&val));
+    match same_type {
+        Some(true) => {
+            let mut node = doc.root.pathto_mut(path);
+            node <<= val;
+            return TOMLOPER_OK;
+        },
+        Some(false) => return TOMLOPER_TYPE_MISMATCH,
+        None => {},
+    }
+
+    let path = path.trim_end_matches(['/', '.']);
+    let (parent, key) = path.rsplit_once(['/', '.']).unwrap_or(("", path));
+    if key.is_empty() {
+        return TOMLOPER_NOT_FOUND;
+    }
+    let table = doc.root.pathto_mut(parent) << (key, val);
+    if !table {
+        return TOMLOPER_NOT_FOUND;
+    }
+    return TOMLOPER_OK;
+}
+
+/// Parse toml string, and store new document handle to `*out` on success.
+///
+/// # Safety
+/// `src` must be null or valid C string, `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_parse(src: *const c_char, out: *mut *mut TomloperDoc) -> c_int {
+    if out.is_null() {
+        return TOMLOPER_NULL_ARG;
+    }
+    *out = ptr::null_mut();
+    let src = match borrow_str(src) {
+        Ok(src) => src,
+        Err(code) => return code,
+    };
+    match src.parse::<Value>() {
+        Ok(root) => {
+            *out = Box::into_raw(Box::new(TomloperDoc { root }));
+            return TOMLOPER_OK;
+        },
+        Err(_) => return TOMLOPER_PARSE_ERROR,
+    }
+}
+
+/// Release document handle.
+///
+/// # Safety
+/// `doc` must be null or handle from `tomloper_parse()` that is not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_free(doc: *mut TomloperDoc) {
+    if !doc.is_null() {
+        drop(Box::from_raw(doc));
+    }
+}
+
+/// Release string returned by this library.
+///
+/// # Safety
+/// `s` must be null or string from `tomloper_get_str()` or `tomloper_serialize()`.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Get string value of the path or copy of `dflt`, caller should free the result.
+/// Return null only if `dflt` is null and the value is not found.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` and `dflt` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_get_str(doc: *const TomloperDoc, path: *const c_char, dflt: *const c_char) -> *mut c_char {
+    let found = lookup(doc, path)
+        .and_then(|node| *node.unpath())
+        .and_then(Value::as_str)
+        .and_then(|s| CString::new(s).ok());
+    match found {
+        Some(s) => s.into_raw(),
+        None if dflt.is_null() => ptr::null_mut(),
+        None => CStr::from_ptr(dflt).to_owned().into_raw(),
+    }
+}
+
+/// Get integer value of the path or `dflt`.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_get_int(doc: *const TomloperDoc, path: *const c_char, dflt: i64) -> i64 {
+    lookup(doc, path).map_or(dflt, |node| node | dflt)
+}
+
+/// Get float value of the path or `dflt`.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_get_float(doc: *const TomloperDoc, path: *const c_char, dflt: f64) -> f64 {
+    lookup(doc, path).map_or(dflt, |node| node | dflt)
+}
+
+/// Get bool value of the path or `dflt`.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_get_bool(doc: *const TomloperDoc, path: *const c_char, dflt: bool) -> bool {
+    lookup(doc, path).map_or(dflt, |node| node | dflt)
+}
+
+/// Set string value to the path.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` and `val` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_set_str(doc: *mut TomloperDoc, path: *const c_char, val: *const c_char) -> c_int {
+    match borrow_str(val) {
+        Ok(val) => set_value(doc, path, Value::from(val)),
+        Err(code) => code,
+    }
+}
+
+/// Set integer value to the path.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_set_int(doc: *mut TomloperDoc, path: *const c_char, val: i64) -> c_int {
+    set_value(doc, path, Value::from(val))
+}
+
+/// Set float value to the path.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_set_float(doc: *mut TomloperDoc, path: *const c_char, val: f64) -> c_int {
+    set_value(doc, path, Value::from(val))
+}
+
+/// Set bool value to the path.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `path` must be null or valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_set_bool(doc: *mut TomloperDoc, path: *const c_char, val: bool) -> c_int {
+    set_value(doc, path, Value::from(val))
+}
+
+/// Write document as toml string to `*out`, caller should free it.
+///
+/// # Safety
+/// `doc` must be null or valid handle, `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tomloper_serialize(doc: *const TomloperDoc, out: *mut *mut c_char) -> c_int {
+    if out.is_null() {
+        return TOMLOPER_NULL_ARG;
+    }
+    *out = ptr::null_mut();
+    let Some(doc) = doc.as_ref() else {
+        return TOMLOPER_NULL_ARG;
+    };
+    let text = toml::to_string(&doc.root).ok().and_then(|s| CString::new(s).ok());
+    match text {
+        Some(text) => {
+            *out = text.into_raw();
+            return TOMLOPER_OK;
+        },
+        None => return TOMLOPER_SERIALIZE_ERROR,
+    }
+}
+
+/// Static message of error code, never null and need not free.
+#[no_mangle]
+pub extern "C" fn tomloper_strerror(code: c_int) -> *const c_char {
+    let msg = match code {
+        TOMLOPER_OK => c"ok",
+        TOMLOPER_NULL_ARG => c"null pointer argument",
+        TOMLOPER_BAD_UTF8 => c"string is not valid UTF-8",
+        TOMLOPER_PARSE_ERROR => c"invalid toml",
+        TOMLOPER_NOT_FOUND => c"path not found",
+        TOMLOPER_TYPE_MISMATCH => c"type mismatch",
+        TOMLOPER_SERIALIZE_ERROR => c"cannot serialize toml",
+        _ => c"unknown error",
+    };
+    msg.as_ptr()
+}
+
+#[cfg(test)]
+mod tests;
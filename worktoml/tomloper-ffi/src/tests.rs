@@ -0,0 +1,112 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+
+fn load_test_doc() -> *mut TomloperDoc
+{
+    let str_toml = CString::new(include_str!("../../tomloper/examples/sample.toml")).unwrap();
+    let mut doc = ptr::null_mut();
+    let code = unsafe { tomloper_parse(str_toml.as_ptr(), &mut doc) };
+    assert_eq!(code, TOMLOPER_OK);
+    return doc;
+}
+
+/// Take the owned string back to Rust.
+fn take_str(s: *mut c_char) -> Option<String>
+{
+    if s.is_null() {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(s).to_str().unwrap().to_string() };
+    unsafe { tomloper_string_free(s) };
+    return Some(text);
+}
+
+#[test]
+fn parse_test() {
+    let mut doc = ptr::null_mut();
+    unsafe {
+        assert_eq!(tomloper_parse(c"[host".as_ptr(), &mut doc), TOMLOPER_PARSE_ERROR);
+        assert_eq!(doc.is_null(), true);
+        assert_eq!(tomloper_parse(ptr::null(), &mut doc), TOMLOPER_NULL_ARG);
+        assert_eq!(tomloper_parse(c"a = 1".as_ptr(), ptr::null_mut()), TOMLOPER_NULL_ARG);
+        assert_eq!(tomloper_parse(c"\xff = 1".as_ptr(), &mut doc), TOMLOPER_BAD_UTF8);
+
+        // free null is no-op
+        tomloper_free(ptr::null_mut());
+        tomloper_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+#[allow(clippy::approx_constant)]
+fn get_test() {
+    let doc = load_test_doc();
+    unsafe {
+        assert_eq!(take_str(tomloper_get_str(doc, c"host/ip".as_ptr(), c"".as_ptr())).unwrap(), "127.0.1.1");
+        assert_eq!(take_str(tomloper_get_str(doc, c"host.protocol.-1".as_ptr(), ptr::null())).unwrap(), "mmp");
+        assert_eq!(take_str(tomloper_get_str(doc, c"host/port".as_ptr(), c"none".as_ptr())).unwrap(), "none");
+        assert_eq!(take_str(tomloper_get_str(doc, c"host/none".as_ptr(), ptr::null())), None);
+
+        assert_eq!(tomloper_get_int(doc, c"host/port".as_ptr(), 0), 8080);
+        assert_eq!(tomloper_get_int(doc, c"host/ip".as_ptr(), -1), -1);
+        assert_eq!(tomloper_get_float(doc, c"misc/float".as_ptr(), 0.0), 3.14);
+        assert_eq!(tomloper_get_bool(doc, c"misc/bool".as_ptr(), false), true);
+
+        // null doc or path return default
+        assert_eq!(tomloper_get_int(ptr::null(), c"host/port".as_ptr(), 7), 7);
+        assert_eq!(tomloper_get_bool(doc, ptr::null(), true), true);
+        assert_eq!(take_str(tomloper_get_str(ptr::null(), ptr::null(), c"x".as_ptr())).unwrap(), "x");
+        tomloper_free(doc);
+    }
+}
+
+#[test]
+fn set_test() {
+    let doc = load_test_doc();
+    unsafe {
+        assert_eq!(tomloper_set_int(doc, c"host/port".as_ptr(), 9090), TOMLOPER_OK);
+        assert_eq!(tomloper_get_int(doc, c"host/port".as_ptr(), 0), 9090);
+        assert_eq!(tomloper_set_str(doc, c"host/port".as_ptr(), c"80".as_ptr()), TOMLOPER_TYPE_MISMATCH);
+        assert_eq!(tomloper_set_str(doc, c"service/-1/name".as_ptr(), c"last".as_ptr()), TOMLOPER_OK);
+        assert_eq!(take_str(tomloper_get_str(doc, c"service/1/name".as_ptr(), ptr::null())).unwrap(), "last");
+
+        // new key in existing table
+        assert_eq!(tomloper_set_bool(doc, c"host/tls".as_ptr(), true), TOMLOPER_OK);
+        assert_eq!(tomloper_get_bool(doc, c"host/tls".as_ptr(), false), true);
+        assert_eq!(tomloper_set_float(doc, c"ratio".as_ptr(), 0.5), TOMLOPER_OK);
+        assert_eq!(tomloper_get_float(doc, c"ratio".as_ptr(), 0.0), 0.5);
+
+        assert_eq!(tomloper_set_int(doc, c"none/key".as_ptr(), 1), TOMLOPER_NOT_FOUND);
+        assert_eq!(tomloper_set_int(doc, c"host/protocol/9".as_ptr(), 1), TOMLOPER_NOT_FOUND);
+        assert_eq!(tomloper_set_int(ptr::null_mut(), c"a".as_ptr(), 1), TOMLOPER_NULL_ARG);
+        assert_eq!(tomloper_set_str(doc, c"a".as_ptr(), ptr::null()), TOMLOPER_NULL_ARG);
+        tomloper_free(doc);
+    }
+}
+
+#[test]
+fn serialize_test() {
+    let doc = load_test_doc();
+    unsafe {
+        assert_eq!(tomloper_set_int(doc, c"host/port".as_ptr(), 9090), TOMLOPER_OK);
+        let mut text = ptr::null_mut();
+        assert_eq!(tomloper_serialize(doc, &mut text), TOMLOPER_OK);
+        let text = take_str(text).unwrap();
+        let v: Value = text.parse().unwrap();
+        assert_eq!(v.pathto("host/port") | 0, 9090);
+
+        let mut text = c"dangling".as_ptr() as *mut c_char;
+        assert_eq!(tomloper_serialize(ptr::null(), &mut text), TOMLOPER_NULL_ARG);
+        assert_eq!(text.is_null(), true);
+        tomloper_free(doc);
+    }
+}
+
+#[test]
+fn strerror_test() {
+    let msg = |code| unsafe { CStr::from_ptr(tomloper_strerror(code)).to_str().unwrap() };
+    assert_eq!(msg(TOMLOPER_OK), "ok");
+    assert_eq!(msg(TOMLOPER_TYPE_MISMATCH), "type mismatch");
+    assert_eq!(msg(42), "unknown error");
+}
@@ -0,0 +1,61 @@
+/*
+ * Compiled and linked with libtomloper_ffi by tests/header.rs,
+ * exit with non-zero line number of the first failed check.
+ */
+
+#include <string.h>
+
+#include "tomloper.h"
+
+#define CHECK(cond) do { if (!(cond)) return __LINE__; } while (0)
+
+/* Codes are constant expression. */
+_Static_assert(TOMLOPER_NULL_ARG == -1, "TOMLOPER_NULL_ARG");
+_Static_assert(TOMLOPER_SERIALIZE_ERROR == -6, "TOMLOPER_SERIALIZE_ERROR");
+
+/* Every prototype is checked by assignment to the expected function type. */
+static int (*const parse)(const char *, TomloperDoc **) = tomloper_parse;
+static void (*const free_doc)(TomloperDoc *) = tomloper_free;
+static void (*const free_str)(char *) = tomloper_string_free;
+static char *(*const get_str)(const TomloperDoc *, const char *, const char *) = tomloper_get_str;
+static int64_t (*const get_int)(const TomloperDoc *, const char *, int64_t) = tomloper_get_int;
+static double (*const get_float)(const TomloperDoc *, const char *, double) = tomloper_get_float;
+static bool (*const get_bool)(const TomloperDoc *, const char *, bool) = tomloper_get_bool;
+static int (*const set_str)(TomloperDoc *, const char *, const char *) = tomloper_set_str;
+static int (*const set_int)(TomloperDoc *, const char *, int64_t) = tomloper_set_int;
+static int (*const set_float)(TomloperDoc *, const char *, double) = tomloper_set_float;
+static int (*const set_bool)(TomloperDoc *, const char *, bool) = tomloper_set_bool;
+static int (*const serialize)(const TomloperDoc *, char **) = tomloper_serialize;
+static const char *(*const strerror_of)(int) = tomloper_strerror;
+
+int main(void)
+{
+    TomloperDoc *doc = NULL;
+    CHECK(parse("[host]\nport = 8080\nprotocol = [\"tcp\", \"udp\"]\n", &doc) == TOMLOPER_OK);
+    CHECK(get_int(doc, "host/port", 0) == 8080);
+    CHECK(get_float(doc, "host/none", 0.5) == 0.5);
+    CHECK(get_bool(doc, "host/none", true));
+
+    char *proto = get_str(doc, "host.protocol.-1", NULL);
+    CHECK(proto != NULL && strcmp(proto, "udp") == 0);
+    free_str(proto);
+
+    CHECK(set_int(doc, "host/port", 9090) == TOMLOPER_OK);
+    CHECK(set_str(doc, "host/port", "80") == TOMLOPER_TYPE_MISMATCH);
+    CHECK(set_str(doc, "host/ip", "::1") == TOMLOPER_OK);
+    CHECK(set_float(doc, "ratio", 0.25) == TOMLOPER_OK);
+    CHECK(set_bool(doc, "none/key", true) == TOMLOPER_NOT_FOUND);
+
+    char *text = NULL;
+    CHECK(serialize(doc, &text) == TOMLOPER_OK);
+    CHECK(strstr(text, "port = 9090") != NULL);
+    free_str(text);
+
+    free_doc(doc);
+
+    CHECK(parse("[host", &doc) == TOMLOPER_PARSE_ERROR);
+    CHECK(doc == NULL);
+    CHECK(strcmp(strerror_of(TOMLOPER_NULL_ARG), "null pointer argument") == 0);
+    free_doc(NULL);
+    return 0;
+}
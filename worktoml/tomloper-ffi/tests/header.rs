@@ -0,0 +1,109 @@
+//! Check `include/tomloper.h` against the library: the declared functions and codes
+//! match the exported ones in `src/lib.rs`, and a C program compiles and links with it.
+
+#![allow(clippy::bool_assert_comparison)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HEADER: &str = include_str!("../include/tomloper.h");
+const SOURCE: &str = include_str!("../src/lib.rs");
+
+/// Names of exported functions, as `tomloper_parse`.
+fn exported_functions() -> Vec<String>
+{
+    let mut names: Vec<String> = SOURCE.lines()
+        .filter_map(|line| line.split_once("extern \"C\" fn ").map(|(_, tail)| tail))
+        .filter_map(|tail| tail.split_once('(').map(|(name, _)| name.to_string()))
+        .collect();
+    names.sort();
+    return names;
+}
+
+/// Names of functions declared in the header.
+fn declared_functions() -> Vec<String>
+{
+    let mut names: Vec<String> = HEADER.lines()
+        .filter(|line| !line.starts_with(' ') && !line.starts_with('/') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('(').map(|(head, _)| head))
+        .filter_map(|head| head.rsplit([' ', '*']).next())
+        .map(String::from)
+        .collect();
+    names.sort();
+    return names;
+}
+
+/// Error codes as (name, value text), from `pub const` in Rust or `#define` in C.
+fn error_codes(text: &str, prefix: &str, sep: &str) -> Vec<(String, String)>
+{
+    let mut codes: Vec<(String, String)> = text.lines()
+        .filter_map(|line| line.strip_prefix(prefix))
+        .filter_map(|line| line.split_once(sep))
+        .map(|(name, value)| {
+            let value = value.trim().trim_end_matches(';').trim_start_matches('(').trim_end_matches(')');
+            (name.trim().to_string(), value.to_string())
+        })
+        .collect();
+    codes.sort();
+    return codes;
+}
+
+#[test]
+fn declaration_test() {
+    let exported = exported_functions();
+    assert_eq!(exported.len(), 13);
+    assert_eq!(declared_functions(), exported);
+
+    let rust_codes = error_codes(SOURCE, "pub const ", ": c_int = ");
+    let c_codes = error_codes(HEADER, "#define TOMLOPER_", " ");
+    let c_codes: Vec<_> = c_codes.into_iter().map(|(name, value)| (format!("TOMLOPER_{}", name), value)).collect();
+    assert_eq!(rust_codes.len(), 7);
+    assert_eq!(c_codes, rust_codes);
+
+    // negative code is parenthesized
+    for line in HEADER.lines().filter(|line| line.starts_with("#define TOMLOPER_")) {
+        assert_eq!(line.contains(" -"), false, "{}", line);
+    }
+}
+
+/// Directory of the built `libtomloper_ffi`, beside the test executable.
+fn library_dir() -> Option<PathBuf>
+{
+    let exe = std::env::current_exe().ok()?;
+    let deps = exe.parent()?;
+    let name = format!("{}tomloper_ffi{}", std::env::consts::DLL_PREFIX, std::env::consts::DLL_SUFFIX);
+    let found = [deps, deps.parent()?].into_iter()
+        .find(|dir| dir.join(&name).exists())
+        .map(Path::to_path_buf);
+    return found;
+}
+
+#[cfg(unix)]
+#[test]
+fn compile_test() {
+    let Some(lib_dir) = library_dir() else {
+        panic!("libtomloper_ffi is not built beside {:?}", std::env::current_exe());
+    };
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join("tomloper-header-test");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let compiled = Command::new(&cc)
+        .args(["-std=c11", "-Wall", "-Werror"])
+        .arg("-I").arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/header.c"))
+        .arg("-L").arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-ltomloper_ffi")
+        .arg("-o").arg(&exe)
+        .status();
+    match compiled {
+        Ok(status) => assert_eq!(status.success(), true, "{} fails to build tests/header.c", cc),
+        // no C compiler, only the declarations are checked
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => panic!("{}: {}", cc, e),
+    }
+
+    let status = Command::new(&exe).status().unwrap();
+    assert_eq!(status.code(), Some(0), "failed check at line {:?} of tests/header.c", status.code());
+}
@@ -0,0 +1,44 @@
+//! Compare lazy lookup with full parse on a large generated toml text.
+//! Run by: cargo bench --bench lazy
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tomloper::{PathOperator, LazyToml};
+
+/// Generate toml text with `n` tables and an array of `n` tables.
+fn generate(n: usize) -> String {
+    let mut text = String::from("title = \"generated\"\n");
+    for i in 0..n {
+        text.push_str(&format!("\n[node_{}]\nid = {}\nname = \"node {}\"\nweight = {}.5\n", i, i, i, i));
+        text.push_str("tags = [\"alpha\", \"beta\", \"gamma\"]\nlimit = { conn = 100, rate = 2.5 }\n");
+    }
+    for i in 0..n {
+        text.push_str(&format!("\n[[item]]\nid = {}\ndesc = \"\"\"\nitem {}\n[not a header]\n\"\"\"\n", i, i));
+    }
+    return text;
+}
+
+fn bench_lazy(c: &mut Criterion) {
+    let text = generate(20_000);
+    println!("generated toml text: {} bytes", text.len());
+    let lazy = LazyToml::new(text.as_str());
+    assert_eq!(lazy.pathto("node_12345/name") | "", "node 12345");
+
+    let mut group = c.benchmark_group("large");
+    group.sample_size(10);
+    group.bench_function("full_parse", |b| {
+        b.iter(|| {
+            let v: toml::Value = black_box(text.as_str()).parse().unwrap();
+            (v.pathto("node_12345/name") | "").len()
+        })
+    });
+    group.bench_function("lazy_scan_and_lookup", |b| {
+        b.iter(|| LazyToml::new(black_box(text.as_str())).pathto("node_12345/name") | "")
+    });
+    group.bench_function("lazy_lookup", |b| {
+        b.iter(|| lazy.pathto(black_box("node_12345/name")) | "")
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_lazy);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+//! Compare path lookup by string, compiled path and static path.
+//! Run by: cargo bench --bench path
+//!
+//! The allocation count of each lookup is printed before timing,
+//! compiled and static path should not allocate at all.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tomloper::{PathOperator, CompiledPath, toml_path};
+
+/// System allocator that count allocations.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Count allocations made by `f`.
+fn count_alloc<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn load_sample() -> toml::Value {
+    include_str!("../examples/sample.toml").parse().unwrap()
+}
+
+fn report_alloc(v: &toml::Value, compiled: &CompiledPath) {
+    let string = count_alloc(|| { black_box(v.path() / "host/protocol/2" | ""); });
+    let chained = count_alloc(|| { black_box(v.path() / "host" / "protocol" / 2 | ""); });
+    let compiled = count_alloc(|| { black_box(v.path() / compiled | ""); });
+    let fixed = count_alloc(|| { black_box(v.path() / toml_path!(host.protocol[2]) | ""); });
+    println!("allocations per lookup: string {}, chained {}, compiled {}, static {}",
+        string, chained, compiled, fixed);
+    assert_eq!(compiled, 0);
+    assert_eq!(fixed, 0);
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let v = load_sample();
+    let compiled = CompiledPath::new("host/protocol/2");
+    report_alloc(&v, &compiled);
+
+    let mut group = c.benchmark_group("lookup");
+    group.bench_function("string", |b| {
+        b.iter(|| v.path() / black_box("host/protocol/2") | "")
+    });
+    group.bench_function("chained", |b| {
+        b.iter(|| v.path() / black_box("host") / black_box("protocol") / black_box(2) | "")
+    });
+    group.bench_function("compiled", |b| {
+        b.iter(|| v.path() / black_box(&compiled) | "")
+    });
+    group.bench_function("static", |b| {
+        b.iter(|| v.path() / black_box(toml_path!(host.protocol[2])) | "")
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_lookup);
+criterion_main!(benches);
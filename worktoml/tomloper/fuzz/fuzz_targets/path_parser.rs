@@ -0,0 +1,27 @@
+//! Fuzz the path parser with arbitrary path string on the sample toml.
+//! Readonly, mutable and compiled path should resolve the same node, and never panic.
+//!
+//! Run with: cargo +nightly fuzz run path_parser
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use toml::Value;
+use tomloper::{CompiledPath, PathOperator};
+
+const SAMPLE: &str = include_str!("../../examples/sample.toml");
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(p) = std::str::from_utf8(data) else {
+        return;
+    };
+    let v: Value = SAMPLE.parse().unwrap();
+
+    let read = v.pathto(p).unpath().cloned();
+    let compiled = CompiledPath::new(p).apply(&v).cloned();
+    assert_eq!(read, compiled);
+
+    let mut w = v.clone();
+    let write = w.pathto_mut(p).unpath().as_deref().cloned();
+    assert_eq!(read, write);
+});
@@ -0,0 +1,206 @@
+//! Array manipulation on `TomlPtrMut` beyond push by `<<`.
+//! Like `<<`, each method move the pointer and return pointer to the same array,
+//! or invalid pointer when it is not an array or the index is out of range,
+//! so that they can be chained.
+//!
+//! ```rust
+//! use tomloper::PathOperator;
+//! let mut v: toml::Value = "proto = [\"udp\", \"tcp\", \"udp\"]".parse().unwrap();
+//!
+//! let node = (v.path_mut() / "proto").dedup().sort().prepend("quic");
+//! let node = node - 2;
+//! assert_eq!(node / 1 | "", "tcp");
+//! assert_eq!(v.path() / "proto" / 0 | "", "quic");
+//! ```
+
+use std::cmp::Ordering;
+use std::ops::Sub;
+
+use toml::Value;
+
+use crate::{PathOperator, TomlPtrMut};
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Apply `f` if point to array, which return false to invalidate the pointer.
+    fn with_array<F>(mut self, f: F) -> Self
+    where F: FnOnce(&mut Vec<Value>) -> bool
+    {
+        if self.valop.is_none() {
+            return Self::none();
+        }
+        let v = self.valop.take().unwrap();
+        let done = match v.as_array_mut() {
+            Some(array) => f(array),
+            None => false,
+        };
+        if done {
+            return Self::path(v);
+        }
+        return Self::none();
+    }
+
+    /// Insert item at `index`, shifting the following items, and `index` can be the length.
+    pub fn insert_at<T>(self, index: usize, val: T) -> Self
+    where Value: From<T>
+    {
+        self.with_array(|array| {
+            if index > array.len() {
+                return false;
+            }
+            array.insert(index, Value::from(val));
+            return true;
+        })
+    }
+
+    /// Insert item at the beginning of array.
+    pub fn prepend<T>(self, val: T) -> Self
+    where Value: From<T>
+    {
+        self.insert_at(0, val)
+    }
+
+    /// Remove item at `index`, also can use operator `-`.
+    pub fn remove_at(self, index: usize) -> Self {
+        self.with_array(|array| {
+            if index >= array.len() {
+                return false;
+            }
+            array.remove(index);
+            return true;
+        })
+    }
+
+    /// Shorten the array to keep the first `len` items, no effect if it is shorter.
+    pub fn truncate(self, len: usize) -> Self {
+        self.with_array(|array| {
+            array.truncate(len);
+            return true;
+        })
+    }
+
+    /// Sort the array by item value, see `sort_by_path()` for the order.
+    pub fn sort(self) -> Self {
+        self.with_array(|array| {
+            array.sort_by(compare_value);
+            return true;
+        })
+    }
+
+    /// Sort array of tables by the value at sub path `p` of each item,
+    /// items without the sub path are moved to the end.
+    /// Values of different type are ordered by type as:
+    /// bool, integer and float (compared by number), string, datetime, array, table.
+    /// The sort is stable, so tables with same key keep the original order.
+    pub fn sort_by_path(self, p: &str) -> Self {
+        self.with_array(|array| {
+            array.sort_by(|a, b| {
+                let a = a.pathto(p);
+                let b = b.pathto(p);
+                match (a.unpath(), b.unpath()) {
+                    (Some(a), Some(b)) => compare_value(a, b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            });
+            return true;
+        })
+    }
+
+    /// Remove duplicated items, keep the first one, not only the consecutive.
+    pub fn dedup(self) -> Self {
+        self.with_array(|array| {
+            let mut unique: Vec<Value> = Vec::with_capacity(array.len());
+            for item in array.drain(..) {
+                if !unique.contains(&item) {
+                    unique.push(item);
+                }
+            }
+            *array = unique;
+            return true;
+        })
+    }
+
+    /// Append all items, eg. cloned from another toml array.
+    pub fn extend<I, T>(self, items: I) -> Self
+    where I: IntoIterator<Item = T>, Value: From<T>
+    {
+        self.with_array(|array| {
+            array.extend(items.into_iter().map(Value::from));
+            return true;
+        })
+    }
+
+    /// Append cloned items of another toml array, invalid if `other` is not array.
+    pub fn extend_from(self, other: &Value) -> Self {
+        match other.as_array() {
+            Some(items) => self.extend(items.iter().cloned()),
+            None => Self::none(),
+        }
+    }
+}
+
+/// Operator `-` to remove item at index from toml array.
+/// eg: `toml/array/node - 0` to remove the first item.
+impl<'tr> Sub<usize> for TomlPtrMut<'tr> {
+    type Output = Self;
+    fn sub(self, rhs: usize) -> Self::Output {
+        self.remove_at(rhs)
+    }
+}
+
+/// Rank of value type when compare different type.
+fn type_rank(v: &Value) -> u8 {
+    match v {
+        Value::Boolean(_) => 0,
+        Value::Integer(_) | Value::Float(_) => 1,
+        Value::String(_) => 2,
+        Value::Datetime(_) => 3,
+        Value::Array(_) => 4,
+        Value::Table(_) => 5,
+    }
+}
+
+/// Exact order of integer and float, without rounding the integer to float.
+/// The integer comes first if they are equal in value, to keep total order
+/// with `-0.0 < 0.0` of `f64::total_cmp()`.
+fn compare_int_float(i: i64, f: f64) -> Ordering {
+    if f.is_nan() {
+        // as `total_cmp()`, positive NaN is the greatest and negative NaN the least
+        return if f.is_sign_negative() { Ordering::Greater } else { Ordering::Less };
+    }
+    // exact for any i64, and infinity is saturated
+    let t = f.trunc();
+    match (i as i128).cmp(&(t as i128)) {
+        Ordering::Equal if f > t => Ordering::Less,
+        Ordering::Equal if f < t => Ordering::Greater,
+        Ordering::Equal => Ordering::Less,
+        ord => ord,
+    }
+}
+
+/// Total order of toml value used for sort, tables are regarded as equal.
+fn compare_value(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+        (Value::Integer(a), Value::Float(b)) => compare_int_float(*a, *b),
+        (Value::Float(a), Value::Integer(b)) => compare_int_float(*b, *a).reverse(),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Datetime(a), Value::Datetime(b)) => a.to_string().cmp(&b.to_string()),
+        (Value::Array(a), Value::Array(b)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                let ord = compare_value(x, y);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            a.len().cmp(&b.len())
+        },
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,79 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::load_test_toml;
+
+fn protocols(v: &Value) -> Vec<&str> {
+    let node = v.path() / "host" / "protocol";
+    node.unpath().unwrap().as_array().unwrap().iter().map(|s| s.as_str().unwrap()).collect()
+}
+
+#[test]
+fn insert_remove_test() {
+    let mut v = load_test_toml();
+
+    let node = (v.path_mut() / "host" / "protocol").insert_at(1, "quic");
+    assert_eq!(node.is_none(), false);
+    assert_eq!(protocols(&v), vec!["tcp", "quic", "udp", "mmp"]);
+
+    let _ = (v.path_mut() / "host" / "protocol").prepend("http").insert_at(5, "last");
+    assert_eq!(protocols(&v), vec!["http", "tcp", "quic", "udp", "mmp", "last"]);
+
+    let node = (v.path_mut() / "host" / "protocol").insert_at(7, "far");
+    assert_eq!(node.is_none(), true);
+
+    let node = v.path_mut() / "host" / "protocol" - 0 - 1;
+    assert_eq!(node.is_none(), false);
+    assert_eq!(protocols(&v), vec!["tcp", "udp", "mmp", "last"]);
+    let node = (v.path_mut() / "host" / "protocol").remove_at(4);
+    assert_eq!(node.is_none(), true);
+
+    let _ = (v.path_mut() / "host" / "protocol").truncate(2).truncate(3);
+    assert_eq!(protocols(&v), vec!["tcp", "udp"]);
+
+    // not array
+    let node = (v.path_mut() / "host").prepend(1);
+    assert_eq!(node.is_none(), true);
+    let node = v.path_mut() / "host" / "port" - 0;
+    assert_eq!(node.is_none(), true);
+}
+
+#[test]
+fn sort_dedup_test() {
+    let mut v = load_test_toml();
+
+    let _ = v.path_mut() / "host" / "protocol" << ["udp"] << ["abc"] << ["tcp"];
+    let _ = (v.path_mut() / "host" / "protocol").dedup();
+    assert_eq!(protocols(&v), vec!["tcp", "udp", "mmp", "abc"]);
+    let _ = (v.path_mut() / "host" / "protocol").sort();
+    assert_eq!(protocols(&v), vec!["abc", "mmp", "tcp", "udp"]);
+
+    let mut mixed: Value = "a = [3, \"x\", 1.5, true, 2]".parse().unwrap();
+    let _ = (mixed.path_mut() / "a").sort();
+    assert_eq!(mixed["a"].to_string(), "[true, 1.5, 2, 3, \"x\"]");
+
+    // no rounding of large integer, and integer before float of the same value
+    let mut big: Value = "a = [9007199254740992.0, 9007199254740993, 9007199254740992, 0.0, -0.0, 0, -0.5, inf, -inf, nan]".parse().unwrap();
+    let _ = (big.path_mut() / "a").sort();
+    assert_eq!(big["a"].to_string(),
+        "[-inf, -0.5, 0, -0.0, 0.0, 9007199254740992, 9007199254740992.0, 9007199254740993, inf, nan]");
+
+    let _ = v.path_mut() / "service" << (toml::value::Table::new(),);
+    let _ = (v.path_mut() / "service").sort_by_path("desc");
+    assert_eq!(v.path() / "service" / 0 / "name" | "", "serv_2");
+    assert_eq!(v.path() / "service" / 1 / "name" | "", "serv_1");
+    assert_eq!((v.path() / "service" / 2 / "name").is_none(), true);
+}
+
+#[test]
+fn extend_test() {
+    let mut v = load_test_toml();
+
+    let other: Value = Value::from(vec!["x", "y"]);
+    let _ = (v.path_mut() / "host" / "protocol").extend_from(&other).extend(vec!["z"]);
+    assert_eq!(protocols(&v), vec!["tcp", "udp", "mmp", "x", "y", "z"]);
+
+    let node = (v.path_mut() / "host" / "protocol").extend_from(&Value::from(1));
+    assert_eq!(node.is_none(), true);
+    assert_eq!(protocols(&v).len(), 6);
+}
@@ -0,0 +1,95 @@
+//! Convert between toml, json, yaml and ini.
+//!
+//! Usage: tomlconv [--from FMT] [--to FMT] [--path PATH] [--strict] [FILE]
+//!
+//! The input format is guessed from file extension, or toml for stdin.
+//! The output format is json by default, and written to stdout.
+//! Lossy spots are reported to stderr, `--strict` make them fail with exit 1.
+//! `--path` convert only the subtree, eg. `host/protocol` or `service.0`.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use tomloper::{Format, read_as, write_subtree};
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: tomlconv [--from FMT] [--to FMT] [--path PATH] [--strict] [FILE]");
+    eprintln!("FMT: toml, json, yaml, ini");
+    return ExitCode::from(2);
+}
+
+fn main() -> ExitCode {
+    let mut from = None;
+    let mut to = Format::Json;
+    let mut path = String::new();
+    let mut strict = false;
+    let mut file = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let Some(format) = args.next().and_then(|f| f.parse::<Format>().ok()) else {
+                    return usage();
+                };
+                if arg == "--from" { from = Some(format); } else { to = format; }
+            },
+            "--path" => {
+                let Some(p) = args.next() else {
+                    return usage();
+                };
+                path = p;
+            },
+            "--strict" => strict = true,
+            "-h" | "--help" => return usage(),
+            _ if arg.starts_with('-') || file.is_some() => return usage(),
+            _ => file = Some(arg),
+        }
+    }
+
+    let text = match &file {
+        Some(file) => std::fs::read_to_string(file),
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map(|_| text)
+        },
+    };
+    let name = file.as_deref().unwrap_or("<stdin>");
+    let text = match text {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}: {}", name, e);
+            return ExitCode::FAILURE;
+        },
+    };
+    let from = from.or_else(|| file.as_deref().and_then(Format::from_path)).unwrap_or(Format::Toml);
+
+    let input = match read_as(&text, from) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{}: {}", name, e);
+            return ExitCode::FAILURE;
+        },
+    };
+    let output = match write_subtree(&input.value, &path, to) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}: {}", name, e);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let losses: Vec<_> = input.losses.iter().chain(output.losses.iter()).collect();
+    for loss in &losses {
+        eprintln!("{}: warning: {}", name, loss);
+    }
+    if strict && !losses.is_empty() {
+        return ExitCode::FAILURE;
+    }
+
+    print!("{}", output.value);
+    if to == Format::Json {
+        println!();
+    }
+    return ExitCode::SUCCESS;
+}
@@ -0,0 +1,106 @@
+//! Format toml files in canonical layout.
+//!
+//! Usage: tomlfmt [--check] [--unsorted] [--inline] [--inline-aot] [--align]
+//!                [--width N] [--indent N] [FILE]...
+//!
+//! Without `--check` the files are rewritten in place, or read stdin and print to stdout
+//! if no file given. With `--check` report the files that are not canonical and exit 1.
+//! Comments are kept, moved together with the key or table they belong to.
+//! With `--unsorted` keys keep the order in the file, otherwise they are sorted.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use tomloper::{FormatOptions, ArrayOfTables, format_str, check_format};
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: tomlfmt [--check] [--unsorted] [--inline] [--inline-aot] [--align] [--width N] [--indent N] [FILE]...");
+    return ExitCode::from(2);
+}
+
+fn main() -> ExitCode {
+    let mut opts = FormatOptions::default();
+    let mut check = false;
+    let mut files = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--unsorted" => opts.sort_keys = false,
+            "--inline" => opts.inline_tables = true,
+            "--inline-aot" => opts.array_of_tables = ArrayOfTables::Inline,
+            "--align" => opts.align_keys = true,
+            "--width" | "--indent" => {
+                let Some(n) = args.next().and_then(|n| n.parse().ok()) else {
+                    return usage();
+                };
+                if arg == "--width" { opts.max_width = n; } else { opts.indent = n; }
+            },
+            "-h" | "--help" => return usage(),
+            _ if arg.starts_with('-') => return usage(),
+            _ => files.push(arg),
+        }
+    }
+
+    if files.is_empty() {
+        let mut text = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut text) {
+            eprintln!("<stdin>: {}", e);
+            return ExitCode::FAILURE;
+        }
+        return run("<stdin>", &text, &opts, check, None);
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for file in &files {
+        let text = match std::fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("{}: {}", file, e);
+                status = ExitCode::FAILURE;
+                continue;
+            },
+        };
+        if run(file, &text, &opts, check, Some(file)) != ExitCode::SUCCESS {
+            status = ExitCode::FAILURE;
+        }
+    }
+    return status;
+}
+
+/// Check or format one text, write back to `output` file or stdout.
+fn run(name: &str, text: &str, opts: &FormatOptions, check: bool, output: Option<&str>) -> ExitCode {
+    if check {
+        return match check_format(text, opts) {
+            Ok(None) => ExitCode::SUCCESS,
+            Ok(Some(line)) => {
+                println!("{}:{}: not formatted", name, line);
+                ExitCode::FAILURE
+            },
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    let formatted = match format_str(text, opts) {
+        Ok(formatted) => formatted,
+        Err(e) => {
+            eprintln!("{}: {}", name, e);
+            return ExitCode::FAILURE;
+        },
+    };
+    match output {
+        Some(file) if formatted != text => {
+            if let Err(e) = std::fs::write(file, formatted) {
+                eprintln!("{}: {}", file, e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(_) => {},
+        None => print!("{}", formatted),
+    }
+    return ExitCode::SUCCESS;
+}
@@ -0,0 +1,118 @@
+//! Interactive shell to explore and patch toml file.
+//!
+//! Usage: tomlsh FILE
+//!
+//! Type `help` for commands, tab to complete keys, `quit` or Ctrl-D to exit.
+
+use std::process::ExitCode;
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use tomloper::Shell;
+
+const HELP: &str = "\
+PATH | get PATH      print the node, eg. host/protocol/1
+cd [PATH]            change current node, to root if no path
+ls [PATH]            list children of table or array
+pwd                  print current path
+set PATH VALUE       set value in toml syntax or plain string
+del PATH             remove key or array item
+save [FILE]          write the document back to file
+quit                 exit, twice if there are unsaved changes";
+
+const COMMANDS: [&str; 9] = ["cd", "del", "get", "help", "ls", "pwd", "quit", "save", "set"];
+
+/// Line editor helper that complete commands and keys.
+struct ShellHelper {
+    shell: Shell,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &line[start..pos];
+        let mut candidates = self.shell.complete(word);
+        if start == 0 {
+            let commands = COMMANDS.iter().filter(|c| c.starts_with(word)).map(|c| c.to_string());
+            candidates.extend(commands);
+        }
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+fn main() -> ExitCode {
+    let Some(file) = std::env::args().nth(1) else {
+        eprintln!("Usage: tomlsh FILE");
+        return ExitCode::from(2);
+    };
+    let shell = match Shell::open(&file) {
+        Ok(shell) => shell,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let mut rl: Editor<ShellHelper, DefaultHistory> = match Editor::new() {
+        Ok(rl) => rl,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        },
+    };
+    rl.set_helper(Some(ShellHelper { shell }));
+
+    let mut warned = false;
+    loop {
+        let prompt = format!("{}> ", rl.helper().map(|h| h.shell.pwd()).unwrap_or_default());
+        let line = match rl.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => String::from("quit"),
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            },
+        };
+        let _ = rl.add_history_entry(line.as_str());
+        let Some(helper) = rl.helper_mut() else {
+            return ExitCode::FAILURE;
+        };
+
+        match line.trim() {
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => {
+                if helper.shell.is_dirty() && !warned {
+                    println!("unsaved changes, save or quit again to discard");
+                    warned = true;
+                    continue;
+                }
+                return ExitCode::SUCCESS;
+            },
+            line => match helper.shell.exec(line) {
+                Ok(out) if out.is_empty() => {},
+                Ok(out) => println!("{}", out),
+                Err(e) => println!("error: {}", e),
+            },
+        }
+        warned = false;
+    }
+}
@@ -0,0 +1,142 @@
+//! Semantic equality of two toml trees, with float tolerance and key normalization,
+//! report the first differing path rather than only `false`.
+//!
+//! ```rust
+//! use tomloper::{EqOptions, semantic_eq, assert_semantic_eq};
+//! let a: toml::Value = "[misc]\nfloat = 3.14\nint = 2\npoint = { x = 1, y = 2 }".parse().unwrap();
+//! let b: toml::Value = "[misc]\nfloat = 3.1400000001\nint = 2.0\npoint = { y = 2, x = 1 }".parse().unwrap();
+//!
+//! let diff = semantic_eq(&a, &b, EqOptions::default()).unwrap_err();
+//! assert_eq!(diff.path, "misc.float");
+//!
+//! let opts = EqOptions { epsilon: 1e-6, int_float: true, ..Default::default() };
+//! assert_eq!(semantic_eq(&a, &b, opts).is_ok(), true);
+//! assert_semantic_eq!(a, b, opts);
+//! ```
+
+use std::fmt;
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::join_key;
+
+/// Options for `semantic_eq()`, all off by default as strict `==`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EqOptions {
+    /// Max absolute difference of two equal floats.
+    pub epsilon: f64,
+    /// Integer equal to float of the same number, eg. `2` and `2.0`.
+    pub int_float: bool,
+    /// Compare table keys case-insensitively.
+    pub ignore_case: bool,
+}
+
+/// The first differing spot of two toml trees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Dotted path, empty for the root.
+    pub path: String,
+    /// Node in the left tree, `None` if missing.
+    pub left: Option<Value>,
+    /// Node in the right tree, `None` if missing.
+    pub right: Option<Value>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<Value>| match v {
+            Some(v) => crate::format::format_inline(v),
+            None => String::from("(missing)"),
+        };
+        let path = if self.path.is_empty() { "(root)" } else { &self.path };
+        write!(f, "toml differ at {}: {} != {}", path, show(&self.left), show(&self.right))
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Compare two toml trees, key order of table never matter.
+/// Return the first differing path in key order.
+pub fn semantic_eq(a: &Value, b: &Value, opts: EqOptions) -> Result<(), Mismatch> {
+    compare(a, b, "", &opts)
+}
+
+/// Assert two toml trees are semantic equal, panic with the first differing path.
+/// eg: `assert_semantic_eq!(a, b)` or `assert_semantic_eq!(a, b, opts)`.
+#[macro_export]
+macro_rules! assert_semantic_eq {
+    ($left:expr, $right:expr) => {
+        $crate::assert_semantic_eq!($left, $right, $crate::EqOptions::default())
+    };
+    ($left:expr, $right:expr, $opts:expr) => {
+        if let Err(diff) = $crate::semantic_eq(&$left, &$right, $opts) {
+            panic!("assertion failed: {}", diff);
+        }
+    };
+}
+
+fn mismatch(path: &str, a: Option<&Value>, b: Option<&Value>) -> Result<(), Mismatch> {
+    Err(Mismatch { path: path.to_string(), left: a.cloned(), right: b.cloned() })
+}
+
+fn compare(a: &Value, b: &Value, path: &str, opts: &EqOptions) -> Result<(), Mismatch> {
+    let equal = match (a, b) {
+        (Value::Float(x), Value::Float(y)) => float_eq(*x, *y, opts),
+        (Value::Integer(x), Value::Float(y)) if opts.int_float => float_eq(*x as f64, *y, opts),
+        (Value::Float(x), Value::Integer(y)) if opts.int_float => float_eq(*x, *y as f64, opts),
+        (Value::Array(x), Value::Array(y)) => {
+            for i in 0..x.len().max(y.len()) {
+                let sub = join_key(path, &i.to_string());
+                match (x.get(i), y.get(i)) {
+                    (Some(l), Some(r)) => compare(l, r, &sub, opts)?,
+                    (l, r) => return mismatch(&sub, l, r),
+                }
+            }
+            true
+        },
+        (Value::Table(x), Value::Table(y)) => return compare_table(x, y, path, opts),
+        _ => a == b,
+    };
+    if !equal {
+        return mismatch(path, Some(a), Some(b));
+    }
+    Ok(())
+}
+
+fn float_eq(x: f64, y: f64, opts: &EqOptions) -> bool {
+    if x.is_nan() || y.is_nan() {
+        return x.is_nan() && y.is_nan();
+    }
+    x == y || (x - y).abs() <= opts.epsilon
+}
+
+fn compare_table(a: &Table, b: &Table, path: &str, opts: &EqOptions) -> Result<(), Mismatch> {
+    let norm = |k: &str| if opts.ignore_case { k.to_lowercase() } else { k.to_string() };
+    let mut keys: Vec<(String, &str)> = a.keys().chain(b.keys())
+        .map(|k| (norm(k), k.as_str()))
+        .collect();
+    keys.sort();
+    keys.dedup_by(|x, y| x.0 == y.0);
+
+    for (key, name) in keys {
+        let (l, r) = (get_key(a, &key, opts), get_key(b, &key, opts));
+        let sub = join_key(path, name);
+        match (l, r) {
+            (Some(l), Some(r)) => compare(l, r, &sub, opts)?,
+            (l, r) => return mismatch(&sub, l, r),
+        }
+    }
+    Ok(())
+}
+
+/// Get value by normalized key.
+fn get_key<'t>(table: &'t Table, key: &str, opts: &EqOptions) -> Option<&'t Value> {
+    if !opts.ignore_case {
+        return table.get(key);
+    }
+    table.iter().find(|(k, _)| k.to_lowercase() == key).map(|(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests;
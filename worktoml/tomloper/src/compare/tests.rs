@@ -0,0 +1,61 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_toml;
+
+#[test]
+fn strict_test() {
+    let v = load_test_toml();
+    let mut w = v.clone();
+    assert_eq!(semantic_eq(&v, &w, EqOptions::default()), Ok(()));
+    assert_semantic_eq!(v, w);
+
+    let _ = w.path_mut() / "service" / 1 / "desc" << "changed";
+    let diff = semantic_eq(&v, &w, EqOptions::default()).unwrap_err();
+    assert_eq!(diff.path, "service.1.desc");
+    assert_eq!(diff.to_string(), r#"toml differ at service.1.desc: "another server" != "changed""#);
+
+    let _ = w.path_mut() / "host" / "protocol" << ("quic",);
+    let diff = semantic_eq(&v, &w, EqOptions::default()).unwrap_err();
+    assert_eq!(diff.path, "host.protocol.3");
+    assert_eq!(diff.left, None);
+    assert_eq!(diff.right, Some(Value::from("quic")));
+
+    let diff = semantic_eq(&v, &Value::from(1), EqOptions::default()).unwrap_err();
+    assert_eq!(diff.to_string().starts_with("toml differ at (root): "), true);
+}
+
+#[test]
+fn option_test() {
+    let v = load_test_toml();
+    let mut w = v.clone();
+    let _ = w.path_mut() / "misc" / "float" << 3.1400000001;
+    let mut node = w.path_mut() / "misc" / "int";
+    node <<= 1234.0;
+
+    let diff = semantic_eq(&v, &w, EqOptions::default()).unwrap_err();
+    assert_eq!(diff.path, "misc.float");
+    let opts = EqOptions { epsilon: 1e-6, ..Default::default() };
+    assert_eq!(semantic_eq(&v, &w, opts).unwrap_err().path, "misc.int");
+    let opts = EqOptions { epsilon: 1e-6, int_float: true, ..Default::default() };
+    assert_semantic_eq!(v, w, opts);
+
+    let a: Value = "[Host]\nIP = \"127.0.0.1\"".parse().unwrap();
+    let b: Value = "[host]\nip = \"127.0.0.1\"".parse().unwrap();
+    assert_eq!(semantic_eq(&a, &b, opts).unwrap_err().path, "Host");
+    let opts = EqOptions { ignore_case: true, ..opts };
+    assert_eq!(semantic_eq(&a, &b, opts), Ok(()));
+
+    let a: Value = "f = nan".parse().unwrap();
+    assert_eq!(semantic_eq(&a, &a, EqOptions::default()), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "toml differ at host.port: 8080 != 9090")]
+fn assert_test() {
+    let v = load_test_toml();
+    let mut w = v.clone();
+    let _ = w.path_mut() / "host" / "port" << 9090;
+    assert_semantic_eq!(v, w);
+}
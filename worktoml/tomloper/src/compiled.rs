@@ -0,0 +1,120 @@
+//! Path string parsed once and reused, to avoid splitting the string on each lookup.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, CompiledPath};
+//! let v: toml::Value = "[host]\nport = 8080\nprotocol = [\"tcp\", \"udp\"]".parse().unwrap();
+//!
+//! let port_path = CompiledPath::new("host.port");
+//! let proto_path: CompiledPath = "/host/protocol/-1".parse().unwrap();
+//! for _ in 0..3 {
+//!     let port = v.path() / &port_path | 0;
+//!     assert_eq!(port, 8080);
+//!     let proto = v.path() / &proto_path | "";
+//!     assert_eq!(proto, "udp");
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+use std::ops::Div;
+use std::str::FromStr;
+
+use toml::Value;
+
+use crate::{PathBuilder, TomlPtr, TomlPtrMut, step, step_mut};
+
+/// Path parsed from string in the same syntax as `pathto()`,
+/// split on slash(/) or dot(.), while empty segment is dropped.
+/// Numeric segment is taken as array index or as table key depend on the node,
+/// negative index count from the end, just like the string path.
+/// Resolving it does not allocate, so it can be cached for hot lookups.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompiledPath {
+    segments: Vec<String>,
+}
+
+impl CompiledPath {
+    /// Parse path string.
+    pub fn new(p: &str) -> Self {
+        let segments = p.build_path().paths
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect();
+        Self { segments }
+    }
+
+    /// Number of non-empty segments.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Test if it is empty path that point to the node itself.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Resolve path readonly for readonly `toml::Value`.
+    pub fn apply<'tr>(&self, v: &'tr Value) -> Option<&'tr Value> {
+        let mut target = v;
+        for seg in &self.segments {
+            target = step(target, seg)?;
+        }
+        return Some(target);
+    }
+
+    /// Resolve path for mutable `toml::Value`.
+    pub fn apply_mut<'tr>(&self, v: &'tr mut Value) -> Option<&'tr mut Value> {
+        let mut target = v;
+        for seg in &self.segments {
+            target = step_mut(target, seg)?;
+        }
+        return Some(target);
+    }
+}
+
+impl FromStr for CompiledPath {
+    type Err = Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl From<&str> for CompiledPath {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+/// Display as dotted path, eg. `host.protocol.1`.
+impl fmt::Display for CompiledPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, seg) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            f.write_str(seg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Path operator `/` with compiled path, eg. `tomlptr / &compiled`.
+impl<'tr, 'p> Div<&'p CompiledPath> for TomlPtr<'tr>
+{
+    type Output = Self;
+    fn div(self, rhs: &'p CompiledPath) -> Self::Output {
+        TomlPtr { valop: self.valop.and_then(|v| rhs.apply(v)) }
+    }
+}
+
+/// Path operator `/` with compiled path for mutable pointer.
+impl<'tr, 'p> Div<&'p CompiledPath> for TomlPtrMut<'tr>
+{
+    type Output = Self;
+    fn div(self, rhs: &'p CompiledPath) -> Self::Output {
+        TomlPtrMut { valop: self.valop.and_then(|v| rhs.apply_mut(v)) }
+    }
+}
+
+#[cfg(test)]
+mod tests;
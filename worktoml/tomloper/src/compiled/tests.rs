@@ -0,0 +1,52 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use std::collections::HashMap;
+use crate::tests::load_test_toml;
+
+#[test]
+fn compiled_path_test() {
+    let mut v = load_test_toml();
+
+    let path = CompiledPath::new("/host/protocol/2");
+    assert_eq!(path.len(), 3);
+    assert_eq!(path.to_string(), "host.protocol.2");
+    assert_eq!(path, CompiledPath::new("host.protocol..2/"));
+    assert_eq!(CompiledPath::new("//").is_empty(), true);
+
+    let proto = v.path() / &path | "";
+    assert_eq!(proto, "mmp");
+    let proto = v.path() / "host" / &CompiledPath::new("protocol.1") | "";
+    assert_eq!(proto, "udp");
+    let desc = v.path() / &CompiledPath::new("service.1.desc") | "";
+    assert_eq!(desc, "another server");
+    let node = v.path() / &CompiledPath::new("host.protocol.3");
+    assert_eq!(node.is_none(), true);
+    let node = v.path() / &CompiledPath::new("host.port.0");
+    assert_eq!(node.is_none(), true);
+
+    // numeric table key is not taken as index
+    let _ = v.path_mut() / "misc" << ("42", "answer");
+    let path = CompiledPath::new("misc.42");
+    let node = v.path_mut() / &path << "the answer";
+    assert_eq!(node | "", "the answer");
+    let answer = v.path() / &path | "";
+    assert_eq!(answer, "the answer");
+
+    let node = v.path_mut() / &CompiledPath::new("misc.none") << 1;
+    assert_eq!(node.is_none(), true);
+}
+
+#[test]
+fn compiled_path_cache_test() {
+    let v = load_test_toml();
+
+    let mut cache: HashMap<&str, CompiledPath> = HashMap::new();
+    for p in ["ip", "host.port", "misc/int"] {
+        cache.insert(p, p.parse().unwrap());
+    }
+    assert_eq!(v.path() / &cache["ip"] | "", "127.0.0.1");
+    assert_eq!(v.path() / &cache["host.port"] | 0, 8080);
+    assert_eq!(v.path() / &cache["misc/int"] | 0, 1234);
+}
@@ -0,0 +1,107 @@
+//! Conditional write on `TomlPtrMut`, complement to `<<` which overwrite when type match,
+//! and `<<=` which overwrite unconditionally.
+//! Failed condition invalidate the pointer as `<<` does, so check it by `!` or `is_none()`.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, fill_defaults};
+//! let mut v: toml::Value = "[host]\nport = 9090".parse().unwrap();
+//!
+//! let mut host = v.path_mut() / "host";
+//! host |= ("port", 8080);
+//! host |= ("ip", "127.0.0.1");
+//! assert_eq!(v.path() / "host" / "port" | 0, 9090);
+//! assert_eq!(v.path() / "host" / "ip" | "", "127.0.0.1");
+//!
+//! let node = (v.path_mut() / "host" / "port").compare_and_swap(9090, 9191);
+//! assert_eq!(node.is_none(), false);
+//! let node = (v.path_mut() / "host" / "port").compare_and_swap(9090, 9292);
+//! assert_eq!(node.is_none(), true);
+//! assert_eq!(v.path() / "host" / "port" | 0, 9191);
+//!
+//! let defaults: toml::Value = "[host]\nport = 80\nproto = \"tcp\"".parse().unwrap();
+//! let filled = fill_defaults(&mut v, &defaults);
+//! assert_eq!(filled, vec!["host.proto"]);
+//! ```
+
+use std::ops::BitOrAssign;
+
+use toml::Value;
+
+use crate::{PathOperator, TomlPtrMut, join_key};
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Insert key-value pair to table only if the key is absent, keep the existed value.
+    /// Return pointer to the table, or invalid pointer if it is not table.
+    pub fn set_default<K: ToString, T>(mut self, key: K, val: T) -> Self
+    where Value: From<T>
+    {
+        if self.valop.is_none() {
+            return Self::none();
+        }
+        let v = self.valop.take().unwrap();
+        match v.as_table_mut() {
+            Some(table) => {
+                table.entry(key.to_string()).or_insert_with(|| Value::from(val));
+            },
+            None => return Self::none(),
+        }
+        return Self::path(v);
+    }
+
+    /// Replace the node with `new` only if it is equal to `expected` now.
+    /// Return pointer to the replaced node, or invalid pointer if not equal.
+    pub fn compare_and_swap<E, T>(mut self, expected: E, new: T) -> Self
+    where Value: From<E> + From<T>
+    {
+        if self.valop.is_none() {
+            return Self::none();
+        }
+        let v = self.valop.take().unwrap();
+        if *v == Value::from(expected) {
+            return Self::put_val(v, new);
+        }
+        return Self::none();
+    }
+}
+
+/// Operator `|=` to set key-value pair (tuple) into toml table if the key is absent,
+/// like pipe operator `|` provide default value for read.
+/// eg: `toml/table/node |= (k, v)`, the pointer is invalid if not table.
+impl<'tr, K: ToString, T> BitOrAssign<(K, T)> for TomlPtrMut<'tr> where Value: From<T>
+{
+    fn bitor_assign(&mut self, rhs: (K, T)) {
+        let ptr = TomlPtrMut { valop: self.valop.take() };
+        *self = ptr.set_default(rhs.0, rhs.1);
+    }
+}
+
+/// Fill missing keys from `defaults` document into `target`, recursively into sub-tables,
+/// while values already in `target` are kept even if the type differ.
+/// Return the dotted paths that have been filled.
+pub fn fill_defaults(target: &mut Value, defaults: &Value) -> Vec<String> {
+    let mut filled = Vec::new();
+    fill_table(target, defaults, "", &mut filled);
+    return filled;
+}
+
+fn fill_table(target: &mut Value, defaults: &Value, prefix: &str, filled: &mut Vec<String>) {
+    let Some(defaults) = defaults.as_table() else {
+        return;
+    };
+    if !target.is_table() {
+        return;
+    }
+    for (k, v) in defaults {
+        if target.get(k).is_none() {
+            let _ = target.path_mut().set_default(k, v.clone());
+            filled.push(join_key(prefix, k));
+        } else if v.is_table() {
+            if let Some(sub) = target.get_mut(k) {
+                fill_table(sub, v, &join_key(prefix, k), filled);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
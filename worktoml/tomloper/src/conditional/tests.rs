@@ -0,0 +1,82 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::load_test_toml;
+
+#[test]
+fn set_default_test() {
+    let mut v = load_test_toml();
+
+    let node = (v.path_mut() / "host").set_default("port", 80).set_default("debug", false);
+    assert_eq!(node.is_none(), false);
+    assert_eq!(v.path() / "host" / "port" | 0, 8080);
+    assert_eq!(v.path() / "host" / "debug" | true, false);
+
+    let mut node = v.path_mut() / "misc";
+    node |= ("int", 0);
+    node |= ("name", "misc");
+    assert_eq!(node.is_none(), false);
+    assert_eq!(v.path() / "misc" / "int" | 0, 1234);
+    assert_eq!(v.path() / "misc" / "name" | "", "misc");
+
+    // not table
+    let mut node = v.path_mut() / "ip";
+    node |= ("key", 1);
+    assert_eq!(node.is_none(), true);
+    let node = (v.path_mut() / "no-table").set_default("key", 1);
+    assert_eq!(node.is_none(), true);
+}
+
+#[test]
+fn compare_and_swap_test() {
+    let mut v = load_test_toml();
+
+    let node = (v.path_mut() / "ip").compare_and_swap("127.0.0.1", "127.0.0.2");
+    assert_eq!(node | "", "127.0.0.2");
+    let node = (v.path_mut() / "ip").compare_and_swap("127.0.0.1", "127.0.0.3");
+    assert_eq!(node.is_none(), true);
+    assert_eq!(v.path() / "ip" | "", "127.0.0.2");
+
+    // compare whole value, and swap may change type
+    let node = (v.path_mut() / "host" / "protocol").compare_and_swap(vec!["tcp", "udp", "mmp"], "tcp");
+    assert_eq!(node.is_none(), false);
+    assert_eq!(v.path() / "host" / "protocol" | "", "tcp");
+
+    // integer is not equal to float
+    let node = (v.path_mut() / "misc" / "int").compare_and_swap(1234.0, 0);
+    assert_eq!(node.is_none(), true);
+    let node = (v.path_mut() / "misc" / "none").compare_and_swap(1, 0);
+    assert_eq!(node.is_none(), true);
+}
+
+#[test]
+fn fill_defaults_test() {
+    let mut v = load_test_toml();
+    let defaults: Value = r#"
+ip = "0.0.0.0"
+log = "info"
+[host]
+port = 80
+timeout = 30
+protocol = ["http"]
+[misc]
+int = "mistype kept"
+[misc.sub]
+key = "v"
+[[service]]
+name = "default"
+"#.parse().unwrap();
+
+    let filled = fill_defaults(&mut v, &defaults);
+    assert_eq!(filled, vec!["host.timeout", "log", "misc.sub"]);
+    assert_eq!(v.path() / "ip" | "", "127.0.0.1");
+    assert_eq!(v.path() / "host" / "port" | 0, 8080);
+    assert_eq!(v.path() / "host" / "timeout" | 0, 30);
+    assert_eq!(v.path() / "host" / "protocol" / 0 | "", "tcp");
+    assert_eq!(v.path() / "misc" / "int" | 0, 1234);
+    assert_eq!(v.path() / "misc" / "sub" / "key" | "", "v");
+    assert_eq!(v.path() / "service" / 0 / "name" | "", "serv_1");
+
+    // fill again is no-op
+    assert_eq!(fill_defaults(&mut v, &defaults).is_empty(), true);
+}
@@ -0,0 +1,565 @@
+//! Convert `toml::Value` to and from JSON, YAML and INI.
+//!
+//! The conversion does not fail on data that the target format cannot hold,
+//! but make the best effort and report each lossy spot with its dotted path,
+//! such as datetime written as string, or null value dropped.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, Format, LossKind, read_as, write_as, write_subtree};
+//! let v: toml::Value = "[host]\nip = \"127.0.0.1\"\nboot = 1979-05-27T07:32:00Z".parse().unwrap();
+//!
+//! let json = write_as(&v, Format::Json).unwrap();
+//! assert_eq!(json.value, r#"{"host":{"boot":"1979-05-27T07:32:00Z","ip":"127.0.0.1"}}"#);
+//! assert_eq!(json.losses[0].path, "host.boot");
+//! assert_eq!(json.losses[0].kind, LossKind::Datetime);
+//!
+//! let back = read_as(r#"{"port": 8080, "proxy": null}"#, Format::Json).unwrap();
+//! assert_eq!(back.value.path() / "port" | 0, 8080);
+//! assert_eq!(back.losses[0].path, "proxy");
+//!
+//! let ip = write_subtree(&v, "host/ip", Format::Yaml).unwrap();
+//! assert_eq!(ip.value, "127.0.0.1\n");
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::{PathOperator, PathBuilder, join_key};
+use crate::format::format_inline;
+use crate::layered::parse_scalar;
+
+/// Text format that can convert with toml.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Json,
+    Yaml,
+    Ini,
+}
+
+impl Format {
+    /// Guess format from file extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        ext.parse().ok()
+    }
+}
+
+impl FromStr for Format {
+    type Err = ConvertError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "toml" => Ok(Format::Toml),
+            "json" => Ok(Format::Json),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "ini" => Ok(Format::Ini),
+            _ => Err(ConvertError::Unknown(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Format::Toml => "toml",
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Ini => "ini",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Error that the conversion cannot go on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertError {
+    /// Unknown format name.
+    Unknown(String),
+    /// Fail to parse the input text in the format.
+    Parse(Format, String),
+    /// Fail to write the value in the format, eg. toml document must be table.
+    Write(Format, String),
+    /// The path of subtree does not exist.
+    NoPath(String),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Unknown(name) => write!(f, "unknown format: {}", name),
+            ConvertError::Parse(format, msg) => write!(f, "fail to parse {}: {}", format, msg),
+            ConvertError::Write(format, msg) => write!(f, "fail to write {}: {}", format, msg),
+            ConvertError::NoPath(p) => write!(f, "no such path: {}", p),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// What is lost or changed at some spot in conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossKind {
+    /// Datetime written as plain string.
+    Datetime,
+    /// Array mix tables with other types, only valid since toml 1.0.
+    MixedArray,
+    /// Null value dropped, toml has no null.
+    Null,
+    /// Float nan or inf written as null.
+    Float,
+    /// Integer out of the range of i64 converted to float.
+    Integer,
+    /// Key is not string, or cannot be written as is.
+    Key,
+}
+
+impl fmt::Display for LossKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            LossKind::Datetime => "datetime written as string",
+            LossKind::MixedArray => "heterogeneous array",
+            LossKind::Null => "null value dropped",
+            LossKind::Float => "non-finite float written as null",
+            LossKind::Integer => "integer out of range converted to float",
+            LossKind::Key => "key cannot be kept as is",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+/// A lossy spot at dotted path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loss {
+    pub path: String,
+    pub kind: LossKind,
+}
+
+impl fmt::Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+/// Result of conversion, with the lossy spots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted<T> {
+    pub value: T,
+    pub losses: Vec<Loss>,
+}
+
+/// Collect lossy spots while walking the tree.
+#[derive(Default)]
+struct Losses {
+    list: Vec<Loss>,
+}
+
+impl Losses {
+    fn add(&mut self, path: &str, kind: LossKind) {
+        self.list.push(Loss { path: path.to_string(), kind });
+    }
+
+    fn done<T>(self, value: T) -> Converted<T> {
+        Converted { value, losses: self.list }
+    }
+}
+
+/// Test if the array mix tables with other types.
+fn is_mixed(items: &[Value]) -> bool {
+    let tables = items.iter().filter(|v| v.is_table()).count();
+    tables > 0 && tables < items.len()
+}
+
+/// Convert toml to json value.
+pub fn to_json(v: &Value) -> Converted<serde_json::Value> {
+    let mut losses = Losses::default();
+    let json = json_of(v, "", &mut losses);
+    losses.done(json)
+}
+
+fn json_of(v: &Value, path: &str, losses: &mut Losses) -> serde_json::Value {
+    use serde_json::Value as Json;
+    match v {
+        Value::String(s) => Json::from(s.as_str()),
+        Value::Integer(i) => Json::from(*i),
+        Value::Float(f) => {
+            match serde_json::Number::from_f64(*f) {
+                Some(n) => Json::Number(n),
+                None => {
+                    losses.add(path, LossKind::Float);
+                    Json::Null
+                },
+            }
+        },
+        Value::Boolean(b) => Json::from(*b),
+        Value::Datetime(dt) => {
+            losses.add(path, LossKind::Datetime);
+            Json::from(dt.to_string())
+        },
+        Value::Array(array) => {
+            array.iter().enumerate()
+                .map(|(i, v)| json_of(v, &join_key(path, &i.to_string()), losses))
+                .collect()
+        },
+        Value::Table(table) => {
+            let map = table.iter()
+                .map(|(k, v)| (k.clone(), json_of(v, &join_key(path, k), losses)))
+                .collect();
+            Json::Object(map)
+        },
+    }
+}
+
+/// Convert json to toml value, null is dropped.
+pub fn from_json(json: &serde_json::Value) -> Converted<Value> {
+    let mut losses = Losses::default();
+    let v = value_of_json(json, "", &mut losses).unwrap_or_else(|| Value::Table(Table::new()));
+    losses.done(v)
+}
+
+fn value_of_json(json: &serde_json::Value, path: &str, losses: &mut Losses) -> Option<Value> {
+    use serde_json::Value as Json;
+    let v = match json {
+        Json::Null => {
+            losses.add(path, LossKind::Null);
+            return None;
+        },
+        Json::Bool(b) => Value::Boolean(*b),
+        Json::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else {
+                if n.is_u64() {
+                    losses.add(path, LossKind::Integer);
+                }
+                Value::Float(n.as_f64().unwrap_or(f64::NAN))
+            }
+        },
+        Json::String(s) => Value::String(s.clone()),
+        Json::Array(array) => {
+            let items: Vec<Value> = array.iter().enumerate()
+                .filter_map(|(i, v)| value_of_json(v, &join_key(path, &i.to_string()), losses))
+                .collect();
+            if is_mixed(&items) {
+                losses.add(path, LossKind::MixedArray);
+            }
+            Value::Array(items)
+        },
+        Json::Object(map) => {
+            let table = map.iter()
+                .filter_map(|(k, v)| Some((k.clone(), value_of_json(v, &join_key(path, k), losses)?)))
+                .collect();
+            Value::Table(table)
+        },
+    };
+    Some(v)
+}
+
+/// Convert toml to yaml value.
+pub fn to_yaml(v: &Value) -> Converted<serde_yaml::Value> {
+    let mut losses = Losses::default();
+    let yaml = yaml_of(v, "", &mut losses);
+    losses.done(yaml)
+}
+
+fn yaml_of(v: &Value, path: &str, losses: &mut Losses) -> serde_yaml::Value {
+    use serde_yaml::Value as Yaml;
+    match v {
+        Value::String(s) => Yaml::from(s.as_str()),
+        Value::Integer(i) => Yaml::from(*i),
+        Value::Float(f) => Yaml::from(*f),
+        Value::Boolean(b) => Yaml::from(*b),
+        Value::Datetime(dt) => {
+            losses.add(path, LossKind::Datetime);
+            Yaml::from(dt.to_string())
+        },
+        Value::Array(array) => {
+            let items = array.iter().enumerate()
+                .map(|(i, v)| yaml_of(v, &join_key(path, &i.to_string()), losses))
+                .collect();
+            Yaml::Sequence(items)
+        },
+        Value::Table(table) => {
+            let map = table.iter()
+                .map(|(k, v)| (Yaml::from(k.as_str()), yaml_of(v, &join_key(path, k), losses)))
+                .collect();
+            Yaml::Mapping(map)
+        },
+    }
+}
+
+/// Convert yaml to toml value, null is dropped and scalar key is turned to string.
+pub fn from_yaml(yaml: &serde_yaml::Value) -> Converted<Value> {
+    let mut losses = Losses::default();
+    let v = value_of_yaml(yaml, "", &mut losses).unwrap_or_else(|| Value::Table(Table::new()));
+    losses.done(v)
+}
+
+fn value_of_yaml(yaml: &serde_yaml::Value, path: &str, losses: &mut Losses) -> Option<Value> {
+    use serde_yaml::Value as Yaml;
+    let v = match yaml {
+        Yaml::Null => {
+            losses.add(path, LossKind::Null);
+            return None;
+        },
+        Yaml::Bool(b) => Value::Boolean(*b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else {
+                if n.is_u64() {
+                    losses.add(path, LossKind::Integer);
+                }
+                Value::Float(n.as_f64().unwrap_or(f64::NAN))
+            }
+        },
+        Yaml::String(s) => Value::String(s.clone()),
+        Yaml::Sequence(array) => {
+            let items: Vec<Value> = array.iter().enumerate()
+                .filter_map(|(i, v)| value_of_yaml(v, &join_key(path, &i.to_string()), losses))
+                .collect();
+            if is_mixed(&items) {
+                losses.add(path, LossKind::MixedArray);
+            }
+            Value::Array(items)
+        },
+        Yaml::Mapping(map) => {
+            let mut table = Table::new();
+            for (k, v) in map {
+                let key = match k {
+                    Yaml::String(s) => s.clone(),
+                    Yaml::Bool(b) => b.to_string(),
+                    Yaml::Number(n) => n.to_string(),
+                    _ => {
+                        losses.add(&join_key(path, "?"), LossKind::Key);
+                        continue;
+                    },
+                };
+                let sub = join_key(path, &key);
+                if !k.is_string() {
+                    losses.add(&sub, LossKind::Key);
+                }
+                if let Some(v) = value_of_yaml(v, &sub, losses) {
+                    table.insert(key, v);
+                }
+            }
+            Value::Table(table)
+        },
+        Yaml::Tagged(tagged) => return value_of_yaml(&tagged.value, path, losses),
+    };
+    Some(v)
+}
+
+/// Write toml as ini text.
+/// Sub-tables are sections with dotted name like `[a.b]`, and array of tables are
+/// sections with index like `[service.0]`. Values are in toml syntax except plain string,
+/// which is quoted only if it would be read back as other type.
+pub fn to_ini(v: &Value) -> Converted<String> {
+    let mut losses = Losses::default();
+    let mut out = String::new();
+    if let Value::Table(table) = v {
+        ini_section(table, "", &mut out, &mut losses);
+    }
+    else {
+        out.push_str(&ini_scalar(v));
+        out.push('\n');
+    }
+    losses.done(out)
+}
+
+fn ini_section(table: &Table, path: &str, out: &mut String, losses: &mut Losses) {
+    let is_section = |v: &Value| match v {
+        Value::Table(_) => true,
+        Value::Array(array) => !array.is_empty() && array.iter().all(|v| v.is_table()),
+        _ => false,
+    };
+
+    let pairs: Vec<_> = table.iter().filter(|(_, v)| !is_section(v)).collect();
+    if !path.is_empty() && (!pairs.is_empty() || table.is_empty()) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("[{}]\n", path));
+    }
+    for (k, v) in pairs {
+        let sub = join_key(path, k);
+        if !is_ini_key(k) {
+            losses.add(&sub, LossKind::Key);
+        }
+        if let Value::Array(array) = v {
+            if is_mixed(array) {
+                losses.add(&sub, LossKind::MixedArray);
+            }
+        }
+        out.push_str(&format!("{} = {}\n", k, ini_scalar(v)));
+    }
+
+    for (k, v) in table.iter().filter(|(_, v)| is_section(v)) {
+        let sub = join_key(path, k);
+        if !is_ini_key(k) || k.contains('.') || k.parse::<usize>().is_ok() {
+            losses.add(&sub, LossKind::Key);
+        }
+        match v {
+            Value::Table(t) => ini_section(t, &sub, out, losses),
+            Value::Array(array) => {
+                for (i, item) in array.iter().enumerate() {
+                    if let Value::Table(t) = item {
+                        ini_section(t, &join_key(&sub, &i.to_string()), out, losses);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Test if the key can be written in ini without ambiguity.
+fn is_ini_key(key: &str) -> bool {
+    !key.is_empty() && key.trim() == key
+        && !key.contains(['=', '[', ']', ';', '#', '\n'])
+}
+
+fn ini_scalar(v: &Value) -> String {
+    if let Value::String(s) = v {
+        if parse_scalar(s) == *v && s.trim() == s && !s.is_empty() && !s.contains('\n') {
+            return s.clone();
+        }
+    }
+    format_inline(v)
+}
+
+/// Read ini text written by `to_ini()` or by hand.
+/// Comment line start with `;` or `#`, value is parsed as toml scalar or as plain string.
+/// Sections with index name `0..n` under the same parent, as `[service.0]`, are turned back
+/// to array, while keys `0 = ...` are kept in table.
+/// Section name and key that collide with each other is error, rather than dropping either.
+pub fn from_ini(text: &str) -> Result<Value, ConvertError> {
+    let mut root = Value::Table(Table::new());
+    let mut section: Vec<String> = Vec::new();
+    let mut sections = BTreeSet::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        let conflict = || ConvertError::Parse(Format::Ini, format!("line {}: section conflict with key", i + 1));
+        if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            section = name.split('.').map(|s| s.trim().to_string()).collect();
+            ini_table(&mut root, &section).ok_or_else(conflict)?;
+            for n in 1..=section.len() {
+                sections.insert(section[..n].join("."));
+            }
+            continue;
+        }
+        let Some((key, val)) = line.split_once('=') else {
+            return Err(ConvertError::Parse(Format::Ini, format!("line {}: expect key = value", i + 1)));
+        };
+        let table = ini_table(&mut root, &section).ok_or_else(conflict)?;
+        let key = key.trim();
+        if table.get(key).is_some_and(Value::is_table) {
+            return Err(ConvertError::Parse(Format::Ini, format!("line {}: key conflict with section", i + 1)));
+        }
+        table.insert(key.to_string(), parse_scalar(val.trim()));
+    }
+    index_to_array(&mut root, "", &|p: &str| sections.contains(p));
+    return Ok(root);
+}
+
+/// Get or create the table of section.
+fn ini_table<'a>(root: &'a mut Value, section: &[String]) -> Option<&'a mut Table> {
+    let mut target = root;
+    for key in section {
+        let table = target.as_table_mut()?;
+        target = table.entry(key.clone()).or_insert_with(|| Value::Table(Table::new()));
+    }
+    target.as_table_mut()
+}
+
+/// Turn tables with keys `0..n` back to array recursively, if all the keys are made from
+/// array index, which `is_index` tell by the dotted path of the child.
+pub(crate) fn index_to_array(v: &mut Value, path: &str, is_index: &dyn Fn(&str) -> bool) {
+    match v {
+        Value::Table(table) => {
+            table.iter_mut().for_each(|(k, v)| index_to_array(v, &join_key(path, k), is_index));
+            let n = table.len();
+            let indexed = (0..n).all(|i| table.contains_key(&i.to_string()))
+                && table.keys().all(|k| is_index(&join_key(path, k)));
+            if n > 0 && indexed {
+                let items = (0..n).filter_map(|i| table.remove(&i.to_string())).collect();
+                *v = Value::Array(items);
+            }
+        },
+        Value::Array(array) => {
+            for (i, v) in array.iter_mut().enumerate() {
+                index_to_array(v, &join_key(path, &i.to_string()), is_index);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Parse text in the format as toml value.
+pub fn read_as(text: &str, format: Format) -> Result<Converted<Value>, ConvertError> {
+    let parse_error = |e: &dyn fmt::Display| ConvertError::Parse(format, e.to_string());
+    match format {
+        Format::Toml => {
+            let v: Value = text.parse().map_err(|e| parse_error(&e))?;
+            Ok(Losses::default().done(v))
+        },
+        Format::Json => {
+            let json: serde_json::Value = serde_json::from_str(text).map_err(|e| parse_error(&e))?;
+            Ok(from_json(&json))
+        },
+        Format::Yaml => {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| parse_error(&e))?;
+            Ok(from_yaml(&yaml))
+        },
+        Format::Ini => Ok(Losses::default().done(from_ini(text)?)),
+    }
+}
+
+/// Write toml value as text in the format.
+pub fn write_as(v: &Value, format: Format) -> Result<Converted<String>, ConvertError> {
+    let write_error = |e: &dyn fmt::Display| ConvertError::Write(format, e.to_string());
+    match format {
+        Format::Toml => {
+            if !v.is_table() {
+                return Err(ConvertError::Write(format, "document must be table".to_string()));
+            }
+            let text = toml::to_string(v).map_err(|e| write_error(&e))?;
+            Ok(Losses::default().done(text))
+        },
+        Format::Json => {
+            let json = to_json(v);
+            let text = serde_json::to_string(&json.value).map_err(|e| write_error(&e))?;
+            Ok(Converted { value: text, losses: json.losses })
+        },
+        Format::Yaml => {
+            let yaml = to_yaml(v);
+            let text = serde_yaml::to_string(&yaml.value).map_err(|e| write_error(&e))?;
+            Ok(Converted { value: text, losses: yaml.losses })
+        },
+        Format::Ini => Ok(to_ini(v)),
+    }
+}
+
+/// Write only the subtree at path `p` in the format,
+/// the reported lossy paths are still full path from root.
+pub fn write_subtree(v: &Value, p: &str, format: Format) -> Result<Converted<String>, ConvertError> {
+    let Some(node) = *v.pathto(p).unpath() else {
+        return Err(ConvertError::NoPath(p.to_string()));
+    };
+    let mut converted = write_as(node, format)?;
+    let prefix = p.build_path().to_key();
+    for loss in converted.losses.iter_mut() {
+        loss.path = join_key(&prefix, &loss.path).trim_end_matches('.').to_string();
+    }
+    return Ok(converted);
+}
+
+#[cfg(test)]
+mod tests;
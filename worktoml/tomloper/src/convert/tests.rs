@@ -0,0 +1,117 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::load_test_toml;
+
+#[test]
+fn json_test() {
+    let v = load_test_toml();
+    let json = to_json(&v);
+    assert_eq!(json.losses.is_empty(), true);
+    assert_eq!(json.value["host"]["protocol"][1], "udp");
+    assert_eq!(json.value["service"][1]["name"], "serv_2");
+
+    let back = from_json(&json.value);
+    assert_eq!(back.losses.is_empty(), true);
+    assert_eq!(back.value, v);
+
+    let json: serde_json::Value = serde_json::from_str(
+        r#"{"a": [1, {"b": null}], "big": 18446744073709551615, "c": null}"#).unwrap();
+    let back = from_json(&json);
+    assert_eq!(back.value.path() / "a" / 1 / "b" | "none", "none");
+    assert_eq!(back.value.path() / "big" | 0.0 > 1e19, true);
+    let losses: Vec<String> = back.losses.iter().map(|l| l.to_string()).collect();
+    assert_eq!(losses, vec![
+        "a.1.b: null value dropped",
+        "a: heterogeneous array",
+        "big: integer out of range converted to float",
+        "c: null value dropped",
+    ]);
+}
+
+#[test]
+fn yaml_test() {
+    let mut v = load_test_toml();
+    let _ = v.path_mut() / "misc" << ("boot", "1979-05-27T07:32:00Z".parse::<toml::value::Datetime>().unwrap());
+
+    let yaml = write_as(&v, Format::Yaml).unwrap();
+    assert_eq!(yaml.losses, vec![Loss { path: "misc.boot".to_string(), kind: LossKind::Datetime }]);
+    assert_eq!(yaml.value.contains("- desc: first server\n  name: serv_1\n"), true);
+
+    let back = read_as(&yaml.value, Format::Yaml).unwrap();
+    assert_eq!(back.value.path() / "misc" / "boot" | "", "1979-05-27T07:32:00Z");
+    assert_eq!((back.value.path() / "host" / "protocol").unpath(), (v.path() / "host" / "protocol").unpath());
+
+    let back = read_as("1: one\ntrue: yes\n~: none\n", Format::Yaml).unwrap();
+    assert_eq!(back.value.path() / "1" | "", "one");
+    assert_eq!(back.losses.iter().all(|l| l.kind == LossKind::Key), true);
+    assert_eq!(back.losses.len(), 3);
+}
+
+#[test]
+fn ini_test() {
+    let v = load_test_toml();
+    let ini = to_ini(&v);
+    let expect = r#"ip = 127.0.0.1
+
+[host]
+ip = 127.0.1.1
+port = 8080
+protocol = ["tcp", "udp", "mmp"]
+
+[misc]
+bool = true
+float = 3.14
+int = 1234
+
+[service.0]
+desc = first server
+name = serv_1
+
+[service.1]
+desc = another server
+name = serv_2
+"#;
+    assert_eq!(ini.value, expect);
+    assert_eq!(ini.losses.is_empty(), true);
+    assert_eq!(from_ini(&ini.value).unwrap(), v);
+
+    let mut v = Value::Table(Table::new());
+    let _ = v.path_mut() << ("num", "1234") << ("blank", " x ") << ("a.b", 1);
+    let ini = to_ini(&v);
+    assert_eq!(ini.value, "a.b = 1\nblank = \" x \"\nnum = \"1234\"\n");
+    let back = from_ini(&ini.value).unwrap();
+    assert_eq!(back.path() / "num" | "", "1234");
+    assert_eq!(back.path() / "blank" | "", " x ");
+
+    let text = "; comment\n[db.main]\nuser = root\n";
+    let back = from_ini(text).unwrap();
+    assert_eq!(back.path() / "db" / "main" / "user" | "", "root");
+    assert_eq!(from_ini("[a]\nno value"), Err(ConvertError::Parse(Format::Ini, "line 2: expect key = value".to_string())));
+
+    // conflict is reported, not dropped
+    assert_eq!(from_ini("a = 1\n[a]\nb = 2"), Err(ConvertError::Parse(Format::Ini, "line 2: section conflict with key".to_string())));
+    assert_eq!(from_ini("[x.y]\nb = 1\n[x]\ny = 2"), Err(ConvertError::Parse(Format::Ini, "line 4: key conflict with section".to_string())));
+
+    // index keys are table, only index sections are array
+    let back = from_ini("[codes]\n0 = ok\n1 = fail\n[list.0]\nv = 1\n[list.1]\nv = 2\n").unwrap();
+    assert_eq!(back.path() / "codes" / "1" | "", "fail");
+    assert_eq!(back.pathto("codes").unwrap().is_table(), true);
+    assert_eq!(back.pathto("list").unwrap().is_array(), true);
+    assert_eq!(back.pathto("list/-1/v") | 0, 2);
+}
+
+#[test]
+fn subtree_test() {
+    let v = load_test_toml();
+    let json = write_subtree(&v, "host/protocol", Format::Json).unwrap();
+    assert_eq!(json.value, r#"["tcp","udp","mmp"]"#);
+
+    let toml = write_subtree(&v, "service.1", Format::Toml).unwrap();
+    assert_eq!(toml.value, "desc = \"another server\"\nname = \"serv_2\"\n");
+
+    assert_eq!(write_subtree(&v, "host/none", Format::Json), Err(ConvertError::NoPath("host/none".to_string())));
+    assert_eq!(write_subtree(&v, "host/port", Format::Toml).is_err(), true);
+    assert_eq!(Format::from_path("a/b.yml"), Some(Format::Yaml));
+    assert_eq!("xml".parse::<Format>().is_err(), true);
+}
@@ -0,0 +1,126 @@
+//! Flatten toml tree to a map from dotted path to leaf value, and build it back.
+//!
+//! Empty table or array is kept as leaf, so the round trip give back the original tree,
+//! as long as no table key contains dot or slash, or is all numerical char,
+//! which the path syntax cannot tell from array index.
+//! A path that is also the prefix of another path cannot be built back, both of them
+//! would be the same node, so `unflatten` report it as conflict.
+//!
+//! ```rust
+//! use tomloper::{FlattenError, flatten, unflatten};
+//! let v: toml::Value = "[host]\nport = 8080\nprotocol = [\"tcp\", \"udp\"]".parse().unwrap();
+//!
+//! let flat = flatten(&v);
+//! let keys: Vec<&str> = flat.keys().map(|k| k.as_str()).collect();
+//! assert_eq!(keys, vec!["host.port", "host.protocol.0", "host.protocol.1"]);
+//! assert_eq!(unflatten(flat), Ok(v));
+//!
+//! let flat = vec![("a.b".to_string(), 2.into()), ("a".to_string(), 1.into())];
+//! assert_eq!(unflatten(flat), Err(FlattenError::Conflict("a".to_string())));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::join_key;
+use crate::convert::index_to_array;
+
+/// Map each leaf of the tree by its dotted path, the root leaf has empty path.
+pub fn flatten(v: &Value) -> BTreeMap<String, Value> {
+    let mut flat = BTreeMap::new();
+    flatten_into(v, "", &mut flat);
+    return flat;
+}
+
+fn flatten_into(v: &Value, prefix: &str, flat: &mut BTreeMap<String, Value>) {
+    match v {
+        Value::Table(table) if !table.is_empty() => {
+            for (k, v) in table {
+                flatten_into(v, &join_key(prefix, k), flat);
+            }
+        },
+        Value::Array(array) if !array.is_empty() => {
+            for (i, v) in array.iter().enumerate() {
+                flatten_into(v, &join_key(prefix, &i.to_string()), flat);
+            }
+        },
+        _ => { flat.insert(prefix.to_string(), v.clone()); },
+    }
+}
+
+/// Error of building tree back from flat paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlattenError {
+    /// The path is repeated, or is the prefix of another path.
+    Conflict(String),
+}
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlattenError::Conflict(p) => write!(f, "{}: conflict path", p),
+        }
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+/// Build tree from dotted paths, tables with all index keys `0..n` become array.
+/// Fail with the shorter path if one path is repeated or lead to another,
+/// whatever the order they come in.
+pub fn unflatten<I>(flat: I) -> Result<Value, FlattenError>
+where I: IntoIterator<Item = (String, Value)>
+{
+    let mut seen = BTreeSet::new();
+    let mut root = Value::Table(Table::new());
+    for (key, leaf) in flat {
+        if let Some(p) = conflict(&seen, &key) {
+            return Err(FlattenError::Conflict(p));
+        }
+        seen.insert(key.clone());
+        if key.is_empty() {
+            root = leaf;
+            continue;
+        }
+        let mut target = &mut root;
+        let mut segments = key.split('.').peekable();
+        while let Some(seg) = segments.next() {
+            let Some(table) = target.as_table_mut() else {
+                break;
+            };
+            if segments.peek().is_none() {
+                table.insert(seg.to_string(), leaf);
+                break;
+            }
+            target = table.entry(seg.to_string()).or_insert_with(|| Value::Table(Table::new()));
+        }
+    }
+    index_to_array(&mut root, "", &|_: &str| true);
+    return Ok(root);
+}
+
+/// Find the path in `seen` that is the same as `key`, or is prefix of it or the reverse,
+/// the empty path is prefix of any other.
+fn conflict(seen: &BTreeSet<String>, key: &str) -> Option<String> {
+    if seen.contains(key) {
+        return Some(key.to_string());
+    }
+    if seen.contains("") || (key.is_empty() && !seen.is_empty()) {
+        return Some(String::new());
+    }
+    let mut prefix = key.match_indices('.').map(|(i, _)| &key[..i]);
+    if let Some(p) = prefix.find(|p| seen.contains(*p)) {
+        return Some(p.to_string());
+    }
+    let lower = format!("{}.", key);
+    if seen.range(lower.clone()..).next().is_some_and(|p| p.starts_with(&lower)) {
+        return Some(key.to_string());
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,48 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::load_test_toml;
+
+#[test]
+fn flatten_test() {
+    let v = load_test_toml();
+    let flat = flatten(&v);
+    assert_eq!(flat.len(), 13);
+    assert_eq!(flat.get("host.protocol.2"), Some(&Value::from("mmp")));
+    assert_eq!(flat.get("service.1.desc"), Some(&Value::from("another server")));
+    assert_eq!(flat.get("host"), None);
+    assert_eq!(unflatten(flat), Ok(v));
+}
+
+#[test]
+fn empty_test() {
+    let v: Value = "a = []\nb = {}\nc = [[], {}, [1]]".parse().unwrap();
+    let flat = flatten(&v);
+    let keys: Vec<&str> = flat.keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, vec!["a", "b", "c.0", "c.1", "c.2.0"]);
+    assert_eq!(unflatten(flat), Ok(v));
+
+    let scalar = Value::from(1);
+    assert_eq!(flatten(&scalar).get(""), Some(&scalar));
+    assert_eq!(unflatten(flatten(&scalar)), Ok(scalar));
+    assert_eq!(unflatten(Vec::new()), Ok(Value::Table(Table::new())));
+}
+
+#[test]
+fn conflict_test() {
+    let flat = |keys: &[&str]| -> Vec<(String, Value)> {
+        keys.iter().map(|k| (k.to_string(), Value::from(1))).collect()
+    };
+    let conflict = |p: &str| Err(FlattenError::Conflict(p.to_string()));
+    assert_eq!(unflatten(flat(&["a", "a.b"])), conflict("a"));
+    assert_eq!(unflatten(flat(&["a.b", "a"])), conflict("a"));
+    assert_eq!(unflatten(flat(&["a.b.c", "a.b", "x"])), conflict("a.b"));
+    assert_eq!(unflatten(flat(&["a.0", "a.0"])), conflict("a.0"));
+    assert_eq!(unflatten(flat(&["", "a"])), conflict(""));
+    assert_eq!(unflatten(flat(&["a", ""])), conflict(""));
+    assert_eq!(unflatten(flat(&["ab", "a.b", "a-b"])).is_ok(), true);
+
+    // empty table is leaf too
+    let flat = vec![("a".to_string(), Value::Table(Table::new())), ("a.b".to_string(), Value::from(1))];
+    assert_eq!(unflatten(flat), conflict("a"));
+}
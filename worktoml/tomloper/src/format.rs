@@ -0,0 +1,484 @@
+//! Pretty-printer of `toml::Value` in canonical layout with style options,
+//! and check whether a toml text is already in that layout.
+//!
+//! Formatting text by `format_str()` keeps the comments, each one is moved together
+//! with the key, table header or array item that it precede or follow.
+//! Node with comments inside is never written inline, so no comment is dropped.
+//!
+//! ```rust
+//! use tomloper::{FormatOptions, format_toml, format_str, check_format};
+//! let v: toml::Value = "b = 1\na = [1, 2]\n[t]\nx = { y = 1 }\n".parse().unwrap();
+//!
+//! let text = format_toml(&v, &FormatOptions::default());
+//! assert_eq!(text, "a = [1, 2]\nb = 1\n\n[t.x]\ny = 1\n");
+//! assert_eq!(check_format(&text, &FormatOptions::default()).unwrap(), None);
+//!
+//! let opts = FormatOptions { inline_tables: true, align_keys: true, ..Default::default() };
+//! assert_eq!(format_toml(&v, &opts), "a = [1, 2]\nb = 1\nt = { x = { y = 1 } }\n");
+//!
+//! let text = format_str("# about b\nb = 1 # why\na = 2\n", &FormatOptions::default()).unwrap();
+//! assert_eq!(text, "a = 2\n# about b\nb = 1 # why\n");
+//! ```
+
+use std::collections::BTreeMap;
+
+use toml::Value;
+use toml::value::Table;
+use toml_edit::{Decor, DocumentMut, Item};
+
+use crate::join_key;
+
+/// How to write array of tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayOfTables {
+    /// As `[[name]]` sections.
+    Standard,
+    /// As `name = [{ ... }, { ... }]`, wrapped if too wide.
+    Inline,
+}
+
+/// Options for `format_toml()`.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Sort keys of each table, otherwise keep the source order of the text given to `format_str()`.
+    /// `toml::Value` has no source order, so `format_toml()` always write keys sorted.
+    pub sort_keys: bool,
+    /// Write sub-table as inline `{ ... }` if it fit in `max_width`,
+    /// otherwise as `[section]`.
+    pub inline_tables: bool,
+    /// Prefered style for array of tables.
+    pub array_of_tables: ArrayOfTables,
+    /// Line width, longer array is wrapped one item per line.
+    pub max_width: usize,
+    /// Spaces to indent wrapped array items.
+    pub indent: usize,
+    /// Align the `=` of key-value pairs in the same table.
+    pub align_keys: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            sort_keys: true,
+            inline_tables: false,
+            array_of_tables: ArrayOfTables::Standard,
+            max_width: 80,
+            indent: 4,
+            align_keys: false,
+        }
+    }
+}
+
+/// Format toml tree as text.
+pub fn format_toml(v: &Value, opts: &FormatOptions) -> String {
+    format_commented(v, opts, &CommentMap::new(), &KeyOrder::new())
+}
+
+/// Parse toml text and format it, the comments are kept.
+pub fn format_str(text: &str, opts: &FormatOptions) -> Result<String, toml::de::Error> {
+    let v: Value = text.parse()?;
+    // already accepted by `toml` above, the same grammar won't fail here
+    let (comments, order) = match text.parse::<DocumentMut>() {
+        Ok(doc) => {
+            let mut order = KeyOrder::new();
+            collect_order(doc.as_table(), "", &mut order);
+            (collect_comments(&doc), order)
+        },
+        Err(_) => (CommentMap::new(), KeyOrder::new()),
+    };
+    Ok(format_commented(&v, opts, &comments, &order))
+}
+
+fn format_commented(v: &Value, opts: &FormatOptions, comments: &CommentMap, order: &KeyOrder) -> String {
+    let mut printer = Printer { opts, comments, order, out: String::new() };
+    match v {
+        Value::Table(table) => printer.write_table(table, &mut Vec::new(), "", Header::None),
+        other => printer.out = printer.inline(other, ""),
+    }
+    if let Some(root) = comments.get("") {
+        printer.push_lines(&root.trailing, "");
+    }
+    return printer.out;
+}
+
+/// Check if toml text is already formatted, return the first line (1-based) that differ,
+/// or `None` if it is canonical.
+pub fn check_format(text: &str, opts: &FormatOptions) -> Result<Option<usize>, toml::de::Error> {
+    let formatted = format_str(text, opts)?;
+    if formatted == text {
+        return Ok(None);
+    }
+    let mut expect = formatted.lines();
+    for (i, line) in text.lines().enumerate() {
+        if expect.next() != Some(line) {
+            return Ok(Some(i + 1));
+        }
+    }
+    Ok(Some(text.lines().count() + 1))
+}
+
+/// Format value in single line, as the right side of `key = value`.
+pub(crate) fn format_inline(v: &Value) -> String {
+    let opts = FormatOptions::default();
+    let comments = CommentMap::new();
+    let order = KeyOrder::new();
+    Printer { opts: &opts, comments: &comments, order: &order, out: String::new() }.inline(v, "")
+}
+
+/// Write key as bare key if possible, otherwise quoted.
+pub(crate) fn format_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        return key.to_string();
+    }
+    Value::String(key.to_string()).to_string()
+}
+
+/// Test if the value should be written as `[[name]]` sections by default.
+fn is_array_of_tables(v: &Value) -> bool {
+    match v {
+        Value::Array(array) => !array.is_empty() && array.iter().all(|v| v.is_table()),
+        _ => false,
+    }
+}
+
+/// Comments attached to one node, which is key-value, table or array item.
+#[derive(Debug, Default)]
+struct Comments {
+    /// Comment lines before the node.
+    before: Vec<String>,
+    /// Comment at the end of the key-value, header or array item line.
+    after: Option<String>,
+    /// Comment lines at the end of array body, or of the document for root.
+    trailing: Vec<String>,
+}
+
+/// Comments by dotted path of the node, in the same form as the printer path:
+/// formatted keys, and array index for array item.
+type CommentMap = BTreeMap<String, Comments>;
+
+/// Comment lines in decor text, the blank lines are dropped.
+fn comment_lines(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+/// Split decor text that start in the middle of line,
+/// into the comment ending the current line and the comment lines after.
+fn split_comments(raw: &str) -> (Option<String>, Vec<String>) {
+    let (first, rest) = raw.split_once('\n').unwrap_or((raw, ""));
+    let first = first.trim();
+    let same_line = if first.starts_with('#') { Some(first.to_string()) } else { None };
+    return (same_line, comment_lines(rest));
+}
+
+fn decor_prefix(decor: &Decor) -> &str {
+    decor.prefix().and_then(|raw| raw.as_str()).unwrap_or("")
+}
+
+fn decor_suffix(decor: &Decor) -> &str {
+    decor.suffix().and_then(|raw| raw.as_str()).unwrap_or("")
+}
+
+/// Collect all comments in the document by node path.
+fn collect_comments(doc: &DocumentMut) -> CommentMap {
+    let mut map = CommentMap::new();
+    collect_table(doc.as_table(), "", &mut map);
+    let trailing = comment_lines(doc.trailing().as_str().unwrap_or(""));
+    if !trailing.is_empty() {
+        map.entry(String::new()).or_default().trailing = trailing;
+    }
+    map.retain(|_, c| !c.before.is_empty() || c.after.is_some() || !c.trailing.is_empty());
+    return map;
+}
+
+fn collect_table(table: &toml_edit::Table, node: &str, map: &mut CommentMap) {
+    let entry = map.entry(node.to_string()).or_default();
+    entry.before.extend(comment_lines(decor_prefix(table.decor())));
+    entry.after = entry.after.take().or(split_comments(decor_suffix(table.decor())).0);
+
+    for (k, item) in table.iter() {
+        let path = join_key(node, &format_key(k));
+        if let Some(key) = table.key(k) {
+            let before = comment_lines(decor_prefix(key.leaf_decor()));
+            map.entry(path.clone()).or_default().before.extend(before);
+        }
+        match item {
+            Item::Value(v) => {
+                let after = split_comments(decor_suffix(v.decor())).0;
+                map.entry(path.clone()).or_default().after = after;
+                collect_value(v, &path, map);
+            },
+            Item::Table(sub) => collect_table(sub, &path, map),
+            Item::ArrayOfTables(array) => {
+                for (i, sub) in array.iter().enumerate() {
+                    collect_table(sub, &join_key(&path, &i.to_string()), map);
+                }
+            },
+            Item::None => {},
+        }
+    }
+}
+
+/// Comments can be only in multi-line array, as inline table is single line.
+fn collect_value(v: &toml_edit::Value, node: &str, map: &mut CommentMap) {
+    let Some(array) = v.as_array() else {
+        return;
+    };
+    let mut last: Option<String> = None;
+    for (i, item) in array.iter().enumerate() {
+        let path = join_key(node, &i.to_string());
+        // the comment after comma belongs to the next item in decor
+        let (same_line, before) = split_comments(decor_prefix(item.decor()));
+        match (same_line, &last) {
+            (Some(c), Some(prev)) => map.entry(prev.clone()).or_default().after = Some(c),
+            (Some(c), None) => map.entry(path.clone()).or_default().before.push(c),
+            (None, _) => {},
+        }
+        map.entry(path.clone()).or_default().before.extend(before);
+        // only the last item without comma can have comment after it
+        let (same_line, rest) = split_comments(decor_suffix(item.decor()));
+        let entry = map.entry(path.clone()).or_default();
+        entry.after = entry.after.take().or(same_line);
+        map.entry(node.to_string()).or_default().trailing.extend(rest);
+        collect_value(item, &path, map);
+        last = Some(path);
+    }
+    let (same_line, trailing) = split_comments(array.trailing().as_str().unwrap_or(""));
+    match (same_line, last) {
+        (Some(c), Some(prev)) => {
+            let entry = map.entry(prev).or_default();
+            entry.after = entry.after.take().or(Some(c));
+        },
+        (Some(c), None) => map.entry(node.to_string()).or_default().trailing.push(c),
+        (None, _) => {},
+    }
+    map.entry(node.to_string()).or_default().trailing.extend(trailing);
+}
+
+/// Keys of each table in source order, by the same node path as `CommentMap`.
+type KeyOrder = BTreeMap<String, Vec<String>>;
+
+/// Collect key order of the table and all tables under it.
+fn collect_order(table: &dyn toml_edit::TableLike, node: &str, order: &mut KeyOrder) {
+    order.insert(node.to_string(), table.iter().map(|(k, _)| k.to_string()).collect());
+    for (k, item) in table.iter() {
+        let path = join_key(node, &format_key(k));
+        match item {
+            Item::Value(v) => collect_order_value(v, &path, order),
+            Item::Table(sub) => collect_order(sub, &path, order),
+            Item::ArrayOfTables(array) => {
+                for (i, sub) in array.iter().enumerate() {
+                    collect_order(sub, &join_key(&path, &i.to_string()), order);
+                }
+            },
+            Item::None => {},
+        }
+    }
+}
+
+/// Inline table may be in value, or in array at any depth.
+fn collect_order_value(v: &toml_edit::Value, node: &str, order: &mut KeyOrder) {
+    match v {
+        toml_edit::Value::InlineTable(table) => collect_order(table, node, order),
+        toml_edit::Value::Array(array) => {
+            for (i, item) in array.iter().enumerate() {
+                collect_order_value(item, &join_key(node, &i.to_string()), order);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Kind of section header for a table.
+#[derive(Clone, Copy)]
+enum Header {
+    None,
+    Table,
+    ArrayItem,
+}
+
+struct Printer<'o> {
+    opts: &'o FormatOptions,
+    comments: &'o CommentMap,
+    order: &'o KeyOrder,
+    out: String,
+}
+
+impl<'o> Printer<'o> {
+    /// Entries of table at `node` in option order, sorted or in source order.
+    fn entries<'t>(&self, table: &'t Table, node: &str) -> Vec<(&'t String, &'t Value)> {
+        let mut entries: Vec<_> = table.iter().collect();
+        if self.opts.sort_keys {
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+        } else if let Some(keys) = self.order.get(node) {
+            entries.sort_by_key(|(k, _)| keys.iter().position(|key| key == *k).unwrap_or(keys.len()));
+        }
+        return entries;
+    }
+
+    /// Test if the entry is written as section rather than `key = value`.
+    fn is_section(&self, key: &str, v: &Value, node: &str) -> bool {
+        match v {
+            Value::Table(_) => {
+                if !self.opts.inline_tables || self.has_inner_comments(node) {
+                    return true;
+                }
+                format_key(key).len() + 3 + self.inline(v, node).len() > self.opts.max_width
+            },
+            _ => is_array_of_tables(v)
+                && (self.opts.array_of_tables == ArrayOfTables::Standard || self.has_inner_comments(node)),
+        }
+    }
+
+    /// Test if there is comment inside the node, which would be lost if written inline.
+    /// The comments before and after the node itself are not counted.
+    fn has_inner_comments(&self, node: &str) -> bool {
+        if self.comments.get(node).is_some_and(|c| !c.trailing.is_empty()) {
+            return true;
+        }
+        let prefix = format!("{}.", node);
+        self.comments.range(prefix.clone()..)
+            .next()
+            .is_some_and(|(k, _)| k.starts_with(&prefix))
+    }
+
+    fn write_table(&mut self, table: &Table, path: &mut Vec<String>, node: &str, header: Header) {
+        let entries: Vec<_> = self.entries(table, node).into_iter()
+            .map(|(k, v)| (k, v, join_key(node, &format_key(k))))
+            .collect();
+        let (sections, pairs): (Vec<_>, Vec<_>) = entries.into_iter()
+            .partition(|(k, v, at)| self.is_section(k, v, at));
+
+        let name = path.join(".");
+        let own = self.comments.get(node);
+        match header {
+            Header::Table if !pairs.is_empty() || sections.is_empty() || own.is_some() => {
+                self.blank_line();
+                self.write_line("", &format!("[{}]", name), node);
+            },
+            Header::ArrayItem => {
+                self.blank_line();
+                self.write_line("", &format!("[[{}]]", name), node);
+            },
+            _ => {},
+        }
+
+        let keys: Vec<String> = pairs.iter().map(|(k, _, _)| format_key(k)).collect();
+        let key_width = if self.opts.align_keys {
+            keys.iter().map(|k| k.len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        for (key, (_, v, at)) in keys.iter().zip(pairs.iter()) {
+            let line = format!("{:width$} = ", key, width = key_width);
+            let value = self.value(v, line.len(), at, 0);
+            self.write_line("", &(line + &value), at);
+        }
+
+        for (k, v, at) in sections {
+            path.push(format_key(k));
+            match v {
+                Value::Table(sub) => self.write_table(sub, path, &at, Header::Table),
+                Value::Array(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        if let Value::Table(sub) = item {
+                            self.write_table(sub, path, &join_key(&at, &i.to_string()), Header::ArrayItem);
+                        }
+                    }
+                },
+                _ => {},
+            }
+            path.pop();
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+    }
+
+    /// Write the line of the node, with the comments before and after it.
+    fn write_line(&mut self, indent: &str, line: &str, node: &str) {
+        let comments = self.comments.get(node);
+        if let Some(c) = comments {
+            self.push_lines(&c.before, indent);
+        }
+        self.out.push_str(indent);
+        self.out.push_str(line);
+        if let Some(after) = comments.and_then(|c| c.after.as_ref()) {
+            self.out.push(' ');
+            self.out.push_str(after);
+        }
+        self.out.push('\n');
+    }
+
+    fn push_lines(&mut self, lines: &[String], indent: &str) {
+        for line in lines {
+            self.out.push_str(indent);
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+    }
+
+    /// Value of `key = value`, wrap the array one item per line if it is too wide
+    /// after `prefix` columns, or there is comment in it.
+    /// Only the item array with comment is wrapped again, at next `depth`.
+    fn value(&self, v: &Value, prefix: usize, node: &str, depth: usize) -> String {
+        let inline = self.inline(v, node);
+        let Value::Array(array) = v else {
+            return inline;
+        };
+        let too_wide = !array.is_empty() && prefix + inline.len() > self.opts.max_width;
+        if !too_wide && !self.has_inner_comments(node) {
+            return inline;
+        }
+
+        let indent = " ".repeat(self.opts.indent * (depth + 1));
+        let mut wrapped = Printer { opts: self.opts, comments: self.comments, order: self.order, out: String::from("[\n") };
+        for (i, item) in array.iter().enumerate() {
+            let at = join_key(node, &i.to_string());
+            let item = match self.has_inner_comments(&at) {
+                true => self.value(item, indent.len(), &at, depth + 1),
+                false => self.inline(item, &at),
+            };
+            wrapped.write_line(&indent, &format!("{},", item), &at);
+        }
+        if let Some(c) = self.comments.get(node) {
+            wrapped.push_lines(&c.trailing, &indent);
+        }
+        wrapped.out.push_str(&" ".repeat(self.opts.indent * depth));
+        wrapped.out.push(']');
+        return wrapped.out;
+    }
+
+    /// Value at `node` in single line.
+    fn inline(&self, v: &Value, node: &str) -> String {
+        match v {
+            Value::Array(array) => {
+                let items: Vec<String> = array.iter().enumerate()
+                    .map(|(i, v)| self.inline(v, &join_key(node, &i.to_string())))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            },
+            Value::Table(table) => {
+                if table.is_empty() {
+                    return String::from("{}");
+                }
+                let items: Vec<String> = self.entries(table, node).iter()
+                    .map(|(k, v)| format!("{} = {}", format_key(k), self.inline(v, &join_key(node, &format_key(k)))))
+                    .collect();
+                format!("{{ {} }}", items.join(", "))
+            },
+            scalar => scalar.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,164 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::{load_test_text, load_test_toml};
+
+#[test]
+fn standard_test() {
+    let v = load_test_toml();
+    let text = format_toml(&v, &FormatOptions::default());
+    let expect = r#"ip = "127.0.0.1"
+
+[host]
+ip = "127.0.1.1"
+port = 8080
+protocol = ["tcp", "udp", "mmp"]
+
+[misc]
+bool = true
+float = 3.14
+int = 1234
+
+[[service]]
+desc = "first server"
+name = "serv_1"
+
+[[service]]
+desc = "another server"
+name = "serv_2"
+"#;
+    assert_eq!(text, expect);
+
+    let back: Value = text.parse().unwrap();
+    assert_eq!(back, v);
+    assert_eq!(check_format(&text, &FormatOptions::default()).unwrap(), None);
+}
+
+#[test]
+fn style_test() {
+    let v = load_test_toml();
+    let opts = FormatOptions {
+        inline_tables: true,
+        array_of_tables: ArrayOfTables::Inline,
+        max_width: 60,
+        align_keys: true,
+        ..Default::default()
+    };
+    let text = format_toml(&v, &opts);
+    let expect = r#"ip      = "127.0.0.1"
+misc    = { bool = true, float = 3.14, int = 1234 }
+service = [
+    { desc = "first server", name = "serv_1" },
+    { desc = "another server", name = "serv_2" },
+]
+
+[host]
+ip       = "127.0.1.1"
+port     = 8080
+protocol = ["tcp", "udp", "mmp"]
+"#;
+    assert_eq!(text, expect);
+
+    let back: Value = text.parse().unwrap();
+    assert_eq!(back, v);
+}
+
+#[test]
+fn key_test() {
+    let v: Value = "\"a b\" = 1\n[\"x.y\".z]\nw = []\n[empty]\n".parse().unwrap();
+    let text = format_toml(&v, &FormatOptions::default());
+    assert_eq!(text, "\"a b\" = 1\n\n[empty]\n\n[\"x.y\".z]\nw = []\n");
+    let back: Value = text.parse().unwrap();
+    assert_eq!(back, v);
+}
+
+#[test]
+fn check_test() {
+    let text = load_test_text();
+    let opts = FormatOptions::default();
+    // the leading comment is kept, but no blank line before `[host]`
+    assert_eq!(check_format(text, &opts).unwrap(), Some(3));
+
+    let text = "a = 1\nc = 3\nb = 2\n";
+    assert_eq!(check_format(text, &opts).unwrap(), Some(2));
+    let text = "a = 1\nb = 2\n";
+    assert_eq!(check_format(text, &opts).unwrap(), None);
+    assert_eq!(check_format("a = ", &opts).is_err(), true);
+}
+
+#[test]
+fn comment_test() {
+    let opts = FormatOptions::default();
+    let text = format_str("# keep me\nb = 1 # why b\na = 2\n", &opts).unwrap();
+    assert_eq!(text, "a = 2\n# keep me\nb = 1 # why b\n");
+
+    let text = r#"# about t
+[t] # t suffix
+y = [
+  1, # one
+  # before two
+  2,
+  # end
+]
+x = 1
+empty = [
+  # nothing
+]
+
+# about s
+[[s]]
+k = { a = 1 } # inline
+# eof
+"#;
+    let expect = r#"# about s
+[[s]]
+k = { a = 1 } # inline
+
+# about t
+[t] # t suffix
+empty = [
+    # nothing
+]
+x = 1
+y = [
+    1, # one
+    # before two
+    2,
+    # end
+]
+# eof
+"#;
+    let inline = FormatOptions { inline_tables: true, array_of_tables: ArrayOfTables::Inline, ..Default::default() };
+    let formatted = format_str(text, &inline).unwrap();
+    assert_eq!(formatted, expect);
+    assert_eq!(check_format(&formatted, &inline).unwrap(), None);
+    let back: Value = formatted.parse().unwrap();
+    assert_eq!(back, text.parse::<Value>().unwrap());
+
+    // without inner comments they are inlined as usual
+    let text = "[[s]]\nk = 1\n\n# about t\n[t] # t\nx = 1\n";
+    assert_eq!(format_str(text, &inline).unwrap(), "s = [{ k = 1 }]\n# about t\nt = { x = 1 } # t\n");
+}
+
+#[test]
+fn unsorted_test() {
+    let opts = FormatOptions { sort_keys: false, ..Default::default() };
+    let text = "b = 1\n\n[a]\nz = 1\n\n[[a.y]]\nq = 1\np = 2\n\n[t]\nx = 2\nw = 1\n\n[[s]]\nn = 1\nm = 2\n";
+    assert_eq!(format_str(text, &opts).unwrap(), text);
+    assert_eq!(check_format(text, &opts).unwrap(), None);
+    assert_eq!(check_format(text, &FormatOptions::default()).unwrap(), Some(7));
+
+    let inline = FormatOptions { inline_tables: true, array_of_tables: ArrayOfTables::Inline, ..opts.clone() };
+    let text = "b = 1\na = { z = 1, y = [{ q = 1, p = 2 }] }\n";
+    assert_eq!(format_str(text, &inline).unwrap(), text);
+
+    // sections still come after key-values
+    let text = "[t]\nx = 1\n\n[u]\ny = 2\n";
+    let moved = format_str(&format!("z = 0\n{}", text), &opts).unwrap();
+    assert_eq!(moved, format!("z = 0\n\n{}", text));
+    assert_eq!(format_str("[u]\ny = 2\n[t]\nx = 1\nb = 0\n", &opts).unwrap(), "[u]\ny = 2\n\n[t]\nx = 1\nb = 0\n");
+
+    // no source order for value tree
+    let v: Value = "b = 1\na = 2".parse().unwrap();
+    assert_eq!(format_toml(&v, &opts), "a = 2\nb = 1\n");
+}
@@ -0,0 +1,255 @@
+//! Layered config merged from several sources, such as toml files, environment variables
+//! and command line overrides, while remember where each leaf value come from.
+//!
+//! The merged tree is plain `toml::Value`, so read it by the usual path operator,
+//! and ask the origin of the same path by `origin()` method:
+//!
+//! ```rust
+//! use tomloper::{PathOperator, LayeredConfig, Origin};
+//! let mut cfg = LayeredConfig::new();
+//! cfg.add_str("base.toml", "[host]\nip = \"127.0.0.1\"\nport = 8080\n").unwrap();
+//! cfg.add_override("host.port", 9090);
+//!
+//! let port = cfg.path() / "host" / "port" | 0;
+//! assert_eq!(port, 9090);
+//! assert_eq!(cfg.origin("host/port"), Some(&Origin::Override));
+//! assert_eq!(cfg.origin("host.ip").unwrap().to_string(), "base.toml:2");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::{PathBuilder, SourceMap, join_key};
+
+/// Where a leaf value in `LayeredConfig` come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Defined in a toml file at line (1-based).
+    File { path: PathBuf, line: usize },
+    /// Read from environment variable with this name.
+    Env(String),
+    /// Set by explicit override, usually from command line.
+    Override,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::File { path, line } => write!(f, "{}:{}", path.display(), line),
+            Origin::Env(name) => write!(f, "env {}", name),
+            Origin::Override => write!(f, "override"),
+        }
+    }
+}
+
+/// Error when add a file layer.
+#[derive(Debug)]
+pub enum LayerError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LayerError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+/// Config tree merged from layers, later layer overwrite the former.
+/// Tables are merged key by key, any other value is replaced as a whole.
+/// Deref to the merged `toml::Value`, so that `path()` and `pathto()` can be used directly.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    value: Value,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl Default for LayeredConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LayeredConfig {
+    /// Construct an empty config with root table.
+    pub fn new() -> Self {
+        Self { value: Value::Table(Table::new()), origins: BTreeMap::new() }
+    }
+
+    /// The merged toml tree.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Get the origin of a leaf node, the path syntax is the same as `pathto()`.
+    /// Return `None` for non-leaf node or non-existed path.
+    pub fn origin(&self, p: &str) -> Option<&Origin> {
+        self.origins.get(&p.build_path().to_key())
+    }
+
+    /// Iterate all leaf paths with their origin, in path order.
+    pub fn origins(&self) -> impl Iterator<Item = (&str, &Origin)> {
+        self.origins.iter().map(|(k, o)| (k.as_str(), o))
+    }
+
+    /// Read and merge a toml file.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self, LayerError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| LayerError::Io(path.to_path_buf(), e))?;
+        self.add_str(path, &text)
+    }
+
+    /// Merge toml text, which is regarded as content of file `path`.
+    pub fn add_str<P: AsRef<Path>>(&mut self, path: P, text: &str) -> Result<&mut Self, LayerError> {
+        let path = path.as_ref();
+        let layer: Value = text.parse()
+            .map_err(|e| LayerError::Parse(path.to_path_buf(), e))?;
+        let map = SourceMap::new(&path.display().to_string(), text);
+        let origin_of = |key: &str| {
+            let line = map.nearest_span(key).map_or(1, |span| span.line);
+            Origin::File { path: path.to_path_buf(), line }
+        };
+        self.merge(layer, &origin_of);
+        Ok(self)
+    }
+
+    /// Merge environment variables start with `prefix`.
+    /// See `add_vars()` for how the variable is mapped.
+    pub fn add_env(&mut self, prefix: &str) -> &mut Self {
+        self.add_vars(prefix, std::env::vars())
+    }
+
+    /// Merge variables start with `prefix` from any name-value iterator.
+    /// The rest name is lowercased and split into path by double underscore,
+    /// eg. `APP_HOST__PORT` to `host.port` with prefix `APP_`.
+    /// The value is parsed as toml value if possible, otherwise kept as string.
+    /// Index segment into existing array must be in range, or the variable is skipped.
+    pub fn add_vars<I>(&mut self, prefix: &str, vars: I) -> &mut Self
+    where I: IntoIterator<Item = (String, String)>
+    {
+        for (name, raw) in vars {
+            let rest = match name.strip_prefix(prefix) {
+                Some(rest) if !rest.is_empty() => rest.to_lowercase(),
+                _ => continue,
+            };
+            let key = rest.split("__").collect::<Vec<_>>().join(".");
+            let layer = nest(&key, parse_scalar(&raw));
+            self.merge(layer, &|_: &str| Origin::Env(name.clone()));
+        }
+        self
+    }
+
+    /// Merge a single value to the path, creating tables on the way if needed.
+    /// Index segment into existing array must be in range, or the override is skipped.
+    pub fn add_override<T>(&mut self, p: &str, val: T) -> &mut Self
+    where Value: From<T>
+    {
+        let layer = nest(&p.build_path().to_key(), Value::from(val));
+        self.merge(layer, &|_: &str| Origin::Override);
+        self
+    }
+
+    /// Merge a layer tree, `origin_of` map leaf path to it's origin.
+    fn merge(&mut self, layer: Value, origin_of: &dyn Fn(&str) -> Origin) {
+        merge_value(&mut self.value, layer, "", &mut self.origins, origin_of);
+    }
+}
+
+/// Deref to the merged `toml::Value`.
+impl Deref for LayeredConfig {
+    type Target = Value;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// Merge `layer` into `base` at path `prefix`, and record origins of the changed leaves.
+fn merge_value(base: &mut Value, layer: Value, prefix: &str,
+    origins: &mut BTreeMap<String, Origin>, origin_of: &dyn Fn(&str) -> Origin)
+{
+    match (base, layer) {
+        (Value::Table(base), Value::Table(layer)) => {
+            for (k, v) in layer {
+                let key = join_key(prefix, &k);
+                match base.get_mut(&k) {
+                    Some(old) => merge_value(old, v, &key, origins, origin_of),
+                    None => {
+                        record_leaves(&v, &key, origins, origin_of);
+                        base.insert(k, v);
+                    }
+                }
+            }
+        },
+        // table with index keys from env or override, merge into array items,
+        // while index out of range is skipped and the array is left alone
+        (Value::Array(base), Value::Table(layer))
+            if layer.keys().all(|k| k.parse::<usize>().is_ok()) => {
+            for (k, v) in layer {
+                let key = join_key(prefix, &k);
+                let index = k.parse::<usize>().unwrap();
+                if let Some(item) = base.get_mut(index) {
+                    merge_value(item, v, &key, origins, origin_of);
+                }
+            }
+        },
+        (base, layer) => {
+            let stale = format!("{}.", prefix);
+            origins.retain(|k, _| k != prefix && !k.starts_with(&stale));
+            record_leaves(&layer, prefix, origins, origin_of);
+            *base = layer;
+        },
+    }
+}
+
+/// Record origins for all leaves under `v`, empty table or array also count as leaf.
+fn record_leaves(v: &Value, prefix: &str,
+    origins: &mut BTreeMap<String, Origin>, origin_of: &dyn Fn(&str) -> Origin)
+{
+    match v {
+        Value::Table(table) if !table.is_empty() => {
+            for (k, v) in table {
+                record_leaves(v, &join_key(prefix, k), origins, origin_of);
+            }
+        },
+        Value::Array(array) if !array.is_empty() => {
+            for (i, v) in array.iter().enumerate() {
+                record_leaves(v, &join_key(prefix, &i.to_string()), origins, origin_of);
+            }
+        },
+        _ => { origins.insert(prefix.to_string(), origin_of(prefix)); },
+    }
+}
+
+/// Build a tree that only has a single value at dotted path `key`.
+fn nest(key: &str, val: Value) -> Value {
+    let mut v = val;
+    for k in key.rsplit('.') {
+        let mut table = Table::new();
+        table.insert(k.to_string(), v);
+        v = Value::Table(table);
+    }
+    return v;
+}
+
+/// Parse a raw string as toml value, fallback to string itself.
+pub(crate) fn parse_scalar(raw: &str) -> Value {
+    let doc = format!("v = {}", raw);
+    match doc.parse::<Value>() {
+        Ok(Value::Table(mut table)) => table.remove("v").unwrap_or_else(|| Value::from(raw)),
+        _ => Value::from(raw),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,104 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_text;
+
+fn sample_config() -> LayeredConfig
+{
+    let str_toml = load_test_text();
+    let mut cfg = LayeredConfig::new();
+    cfg.add_str("sample.toml", str_toml).unwrap();
+    return cfg;
+}
+
+fn file_origin(path: &str, line: usize) -> Origin {
+    Origin::File { path: PathBuf::from(path), line }
+}
+
+#[test]
+fn file_origin_test() {
+    let cfg = sample_config();
+
+    let port = cfg.path() / "host" / "port" | 0;
+    assert_eq!(port, 8080);
+    assert_eq!(cfg.origin("host.port"), Some(&file_origin("sample.toml", 5)));
+    assert_eq!(cfg.origin("/host/port"), Some(&file_origin("sample.toml", 5)));
+    assert_eq!(cfg.origin("ip"), Some(&file_origin("sample.toml", 2)));
+    assert_eq!(cfg.origin("host/protocol/2"), Some(&file_origin("sample.toml", 6)));
+    assert_eq!(cfg.origin("service.1.desc"), Some(&file_origin("sample.toml", 12)));
+    assert_eq!(cfg.origin("misc.bool"), Some(&file_origin("sample.toml", 16)));
+
+    // only leaf has origin
+    assert_eq!(cfg.origin("host"), None);
+    assert_eq!(cfg.origin("host.no-key"), None);
+    assert_eq!(cfg.origin("host.port").unwrap().to_string(), "sample.toml:5");
+}
+
+#[test]
+fn layer_override_test() {
+    let mut cfg = sample_config();
+    cfg.add_str("local.toml", "[host]\nport = 9090\nprotocol = [\"quic\"]\n").unwrap();
+
+    let port = cfg.path() / "host" / "port" | 0;
+    assert_eq!(port, 9090);
+    assert_eq!(cfg.origin("host.port"), Some(&file_origin("local.toml", 2)));
+    // merged table keep other keys
+    assert_eq!(cfg.origin("host.ip"), Some(&file_origin("sample.toml", 4)));
+
+    // array is replaced as a whole, so no stale items
+    let proto = cfg.path() / "host" / "protocol" / 0 | "";
+    assert_eq!(proto, "quic");
+    assert_eq!(cfg.origin("host.protocol.0"), Some(&file_origin("local.toml", 3)));
+    assert_eq!(cfg.origin("host.protocol.1"), None);
+
+    let vars = vec![
+        ("APP_HOST__PORT".to_string(), "7070".to_string()),
+        ("APP_MISC__NAME".to_string(), "not toml value".to_string()),
+        ("APP_SERVICE__1__NAME".to_string(), "serv_b".to_string()),
+        ("OTHER_HOST__PORT".to_string(), "1".to_string()),
+    ];
+    cfg.add_vars("APP_", vars);
+    let port = cfg.path() / "host" / "port" | 0;
+    assert_eq!(port, 7070);
+    assert_eq!(cfg.origin("host.port"), Some(&Origin::Env("APP_HOST__PORT".to_string())));
+    let name = cfg.pathto("misc.name") | "";
+    assert_eq!(name, "not toml value");
+    let name = cfg.pathto("service.1.name") | "";
+    assert_eq!(name, "serv_b");
+    assert_eq!(cfg.origin("service.1.name").unwrap().to_string(), "env APP_SERVICE__1__NAME");
+    assert_eq!(cfg.origin("service.1.desc"), Some(&file_origin("sample.toml", 12)));
+
+    cfg.add_override("host/port", 6060).add_override("host.new.key", true);
+    let port = cfg.path() / "host" / "port" | 0;
+    assert_eq!(port, 6060);
+    assert_eq!(cfg.origin("host.port"), Some(&Origin::Override));
+    let key = cfg.path() / "host" / "new" / "key" | false;
+    assert_eq!(key, true);
+    assert_eq!(cfg.origin("host.new.key"), Some(&Origin::Override));
+}
+
+#[test]
+fn index_out_of_range_test() {
+    let mut cfg = sample_config();
+    let vars = vec![("APP_SERVICE__5__NAME".to_string(), "x".to_string())];
+    cfg.add_vars("APP_", vars);
+    cfg.add_override("host.protocol.3", "quic");
+
+    // skipped, the arrays and their origins are kept
+    assert_eq!(cfg.pathto("service").unwrap().as_array().map(|a| a.len()), Some(2));
+    assert_eq!(cfg.pathto("service.1.name") | "", "serv_2");
+    assert_eq!(cfg.origin("service.1.name"), Some(&file_origin("sample.toml", 11)));
+    assert_eq!(cfg.pathto("host.protocol.-1") | "", "mmp");
+    assert_eq!(cfg.origin("service.5.name"), None);
+}
+
+#[test]
+fn layer_error_test() {
+    let mut cfg = LayeredConfig::new();
+    let err = cfg.add_str("bad.toml", "[host\nport = 1").unwrap_err();
+    assert!(matches!(err, LayerError::Parse(_, _)));
+    let err = cfg.add_file("no/such/file.toml").unwrap_err();
+    assert!(matches!(err, LayerError::Io(_, _)));
+    assert_eq!(cfg.origins().count(), 0);
+}
@@ -0,0 +1,210 @@
+//! Lazy access to large toml text, only parse the tables along the requested path.
+//!
+//! The text is scanned once for `[table]` and `[[array]]` headers without parsing values.
+//! Then `pathto()` parse the root keys and the sections that are ancestor or descendant
+//! of the path, and return `TomlCursor` to read it with the same `/` and `|` operators.
+//!
+//! ```rust
+//! use tomloper::LazyToml;
+//! let lazy = LazyToml::new("title = \"demo\"\n[host]\nport = 8080\n[[service]]\nname = \"s1\"\n[[service]]\nname = \"s2\"\n");
+//! assert_eq!(lazy.sections(), 3);
+//!
+//! assert_eq!(lazy.pathto("host/port") | 0, 8080);
+//! assert_eq!(lazy.pathto("service") / 1 / "name" | "", "s2");
+//! assert_eq!(lazy.pathto("title") | "", "demo");
+//! assert_eq!(lazy.load("host").unwrap().get("service").is_none(), true);
+//! ```
+
+use std::ops::Range;
+use std::path::Path;
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::{TomlDoc, TomlCursor};
+use crate::layered::parse_scalar;
+
+/// A `[header]` and the text range of its section.
+#[derive(Debug, Clone)]
+struct Section {
+    keys: Vec<String>,
+    range: Range<usize>,
+}
+
+/// Toml text indexed by table headers, parsed on demand.
+#[derive(Debug, Clone)]
+pub struct LazyToml {
+    text: String,
+    root_end: usize,
+    sections: Vec<Section>,
+}
+
+impl LazyToml {
+    /// Scan the text for table headers, the text is not validated until loaded.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        let text = text.into();
+        let headers = scan_headers(&text);
+        let root_end = headers.first().map_or(text.len(), |h| h.0);
+        let mut sections = Vec::with_capacity(headers.len());
+        for (i, (start, keys)) in headers.iter().enumerate() {
+            let end = headers.get(i + 1).map_or(text.len(), |h| h.0);
+            sections.push(Section { keys: keys.clone(), range: *start..end });
+        }
+        Self { text, root_end, sections }
+    }
+
+    /// Read and scan toml file.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self::new(std::fs::read_to_string(path)?))
+    }
+
+    /// Number of `[table]` and `[[array]]` sections found.
+    pub fn sections(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Parse the partial document that contains the node at path `p` and all under it.
+    pub fn load(&self, p: &str) -> Result<Value, toml::de::Error> {
+        let request: Vec<&str> = p.split(['/', '.']).filter(|s| !s.is_empty()).collect();
+        let mut partial = String::from(&self.text[..self.root_end]);
+        for section in &self.sections {
+            if is_related(&section.keys, &request) {
+                partial.push('\n');
+                partial.push_str(&self.text[section.range.clone()]);
+            }
+        }
+        partial.parse()
+    }
+
+    /// Cursor to the node at path `p` in the partial document,
+    /// point to nothing if the path not exist or the text is invalid.
+    pub fn pathto(&self, p: &str) -> TomlCursor {
+        let doc = self.load(p).unwrap_or_else(|_| Value::Table(Table::new()));
+        TomlDoc::new(doc).pathto(p)
+    }
+
+    /// Parse the whole document.
+    pub fn path(&self) -> TomlCursor {
+        let doc = self.load("").unwrap_or_else(|_| Value::Table(Table::new()));
+        TomlDoc::new(doc).path()
+    }
+}
+
+/// Test if a header is on the line of request path, either ancestor or descendant.
+/// Header has no array index, so index in request is skipped when not match.
+fn is_related(header: &[String], request: &[&str]) -> bool {
+    match (header.first(), request.first()) {
+        (None, _) | (_, None) => true,
+        (Some(h), Some(r)) if h == r => is_related(&header[1..], &request[1..]),
+        (Some(_), Some(r)) if r.parse::<isize>().is_ok() => is_related(header, &request[1..]),
+        _ => false,
+    }
+}
+
+/// Find the start offset and keys of each header line, skipping strings,
+/// comments and multi-line arrays or inline tables.
+fn scan_headers(text: &str) -> Vec<(usize, Vec<String>)> {
+    let bytes = text.as_bytes();
+    let mut headers = Vec::new();
+    let mut depth = 0usize;
+    let mut line_start = true;
+    let mut line_offset = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if line_start && (c == b' ' || c == b'\t') {
+            i += 1;
+            continue;
+        }
+        if line_start && c == b'[' && depth == 0 {
+            let end = text[i..].find('\n').map_or(text.len(), |n| i + n);
+            if let Some(keys) = parse_header(&text[i..end]) {
+                headers.push((line_offset, keys));
+            }
+            i = end;
+            continue;
+        }
+        line_start = false;
+        match c {
+            b'\n' => {
+                line_start = true;
+                line_offset = i + 1;
+            },
+            b'#' => {
+                i = text[i..].find('\n').map_or(text.len(), |n| i + n);
+                continue;
+            },
+            b'"' | b'\'' => {
+                i = skip_string(bytes, i);
+                continue;
+            },
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+        i += 1;
+    }
+    return headers;
+}
+
+/// Skip string start at `i`, return the offset after its closing quote.
+fn skip_string(bytes: &[u8], i: usize) -> usize {
+    let quote = bytes[i];
+    let multi = bytes[i..].starts_with(&[quote; 3]);
+    let mut j = if multi { i + 3 } else { i + 1 };
+    while j < bytes.len() {
+        let c = bytes[j];
+        if c == b'\\' && quote == b'"' {
+            j += 2;
+            continue;
+        }
+        if multi {
+            if bytes[j..].starts_with(&[quote; 3]) {
+                j += 3;
+                while j < bytes.len() && bytes[j] == quote {
+                    j += 1;
+                }
+                return j;
+            }
+        } else if c == quote || c == b'\n' {
+            return j + 1;
+        }
+        j += 1;
+    }
+    return j;
+}
+
+/// Parse keys of `[a."b.c".d]` or `[[a.b]]` header line.
+fn parse_header(line: &str) -> Option<Vec<String>> {
+    let array = line.starts_with("[[");
+    let mut rest = if array { &line[2..] } else { &line[1..] };
+    let mut keys = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        let (key, tail) = match rest.chars().next()? {
+            '"' => {
+                let end = skip_string(rest.as_bytes(), 0);
+                let key = parse_scalar(rest.get(..end)?);
+                (key.as_str()?.to_string(), &rest[end..])
+            },
+            '\'' => {
+                let end = rest[1..].find('\'')? + 1;
+                (rest[1..end].to_string(), &rest[end+1..])
+            },
+            _ => {
+                let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))?;
+                (rest[..end].to_string(), &rest[end..])
+            },
+        };
+        keys.push(key);
+        rest = tail.trim_start();
+        match rest.chars().next()? {
+            '.' => rest = &rest[1..],
+            ']' => return Some(keys),
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,80 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_text;
+
+#[test]
+fn same_as_full_test() {
+    let text = load_test_text();
+    let v: Value = text.parse().unwrap();
+    let lazy = LazyToml::new(text);
+    assert_eq!(lazy.sections(), 4);
+
+    let paths = ["ip", "host", "host/ip", "host/protocol/2", "service", "service/0/name",
+        "service.1.desc", "misc/float", "misc/none", "none", "service/5"];
+    for p in paths {
+        assert_eq!(lazy.pathto(p).get(), *v.pathto(p).unpath(), "path {}", p);
+    }
+    assert_eq!(lazy.path().get(), Some(&v));
+    assert_eq!(lazy.pathto("host") / "port" | 0, 8080);
+    assert_eq!(!(lazy.pathto("host") / "none"), true);
+}
+
+#[test]
+fn partial_test() {
+    let lazy = LazyToml::new(load_test_text());
+    let host = lazy.load("host/port").unwrap();
+    assert_eq!(host.get("ip").is_some(), true);
+    assert_eq!(host.get("host").is_some(), true);
+    assert_eq!(host.get("service").is_none(), true);
+    assert_eq!(host.get("misc").is_none(), true);
+
+    let service = lazy.load("service/1/name").unwrap();
+    assert_eq!(service.pathto("service/1/name") | "", "serv_2");
+    assert_eq!(service.get("host").is_none(), true);
+}
+
+#[test]
+fn scan_test() {
+    let text = r##"
+s = """
+[fake]
+"""
+l = '''
+[[fake2]]'''
+arr = [
+  [1, 2],
+  ["]", "["],
+] # [comment]
+[a . "b.c" . 'd']
+x = 1
+  [[list]] # indented
+y = "#"
+[list.sub]
+z = { w = [1] }
+[[list]]
+y = "\"["
+"##;
+    let lazy = LazyToml::new(text);
+    assert_eq!(lazy.sections(), 4);
+    let keys: Vec<_> = lazy.sections.iter().map(|s| s.keys.join("/")).collect();
+    assert_eq!(keys, vec!["a/b.c/d", "list", "list/sub", "list"]);
+
+    let v: Value = text.parse().unwrap();
+    assert_eq!(lazy.path().get(), Some(&v));
+    assert_eq!(lazy.pathto("list/0/sub/z/w/0") | 0, 1);
+    assert_eq!(lazy.pathto("list/1/y") | "", "\"[");
+    assert_eq!(lazy.load("a").unwrap().get("list").is_none(), true);
+
+    assert_eq!(is_related(&["ports".to_string(), "8080".to_string()], &["ports", "8080", "x"]), true);
+    assert_eq!(is_related(&["service".to_string(), "sub".to_string()], &["service", "1", "name"]), false);
+}
+
+#[test]
+fn invalid_test() {
+    let lazy = LazyToml::new("[a]\nx = \n[b]\ny = 1\n");
+    assert_eq!(lazy.pathto("b/y") | 0, 1);
+    assert_eq!(lazy.load("a").is_err(), true);
+    assert_eq!(lazy.pathto("a/x").is_none(), true);
+}
@@ -28,6 +28,9 @@
 //! let proto = v.path() / "host" / "proto" / 0 | "";
 //! assert_eq!(proto, "tcp");
 //!
+//! let last = v.path() / "host" / "proto" / "-1" | "";
+//! assert_eq!(last, "udp");
+//!
 //! let host = v.path_mut() / "host";
 //! let host = host << ("newkey", "newval") << ("morekey", 1234);
 //! assert_eq!(host.is_none(), false);
@@ -48,607 +51,131 @@
 //! ```
 //!
 
-use toml::Value;
-use toml::value::Index;
-use std::ops::{Div, BitOr, Shl, ShlAssign, Not, Deref, DerefMut};
-
-/// Resolve path into a `toml::Value` tree.
-/// Return `None` if the path if invalid.
-/// Note the input is aslo `Option`, for symmetrical implementation reason.
-fn path<'tr, B>(v: Option<&'tr Value>, p: B) -> Option<&'tr Value>
-where B: PathBuilder + Index + Copy
-{
-    if v.is_none() {
-        return None;
-    }
-
-    let v = v.unwrap();
-    let from_index = v.get(p);
-    if from_index.is_some() {
-        return from_index;
-    }
-
-    let path_segment = p.build_path();
-    if path_segment.paths.len() > 1 {
-        return path_segment.apply(v);
-    }
-
-    return None;
-}
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-/// Resolve path into a mutable `toml::Value` tree.
-fn path_mut<'tr, B>(v: Option<&'tr mut Value>, p: B) -> Option<&'tr mut Value>
-where B: PathBuilder + Index + Copy
-{
-    if v.is_none() {
-        return None;
-    }
-
-    let v = v.unwrap();
-
-    // Note: use immutable version of get() to determiner path is valid first,
-    // otherwise get_mut() and aplly_mut() would trow E0499 as mut ref twice.
-    let target = v.get(p);
-    if target.is_some() {
-        return v.get_mut(p);
-    }
-    else {
-        let path_segment = p.build_path();
-        if path_segment.paths.len() > 1 {
-            return path_segment.apply_mut(v);
-        }
-        else {
-            return None;
-        }
-    }
-}
+// so that the code generated by derive macro also works inside this crate.
+extern crate self as tomloper;
+extern crate alloc;
 
-/// Path segment break on slash(/) or dot(.).
-/// eg: `table.subtable.key` or `table/subtable/key` or `array/index/key`
-struct PathSegment
-{
-    paths: Vec<String>,
-}
+mod tree;
+pub use tree::{ValueTree, resolve, resolve_mut};
 
-impl PathSegment
-{
-    /// Resolve path readonly for readonly `toml::Value`.
-    fn apply<'tr>(&self, v: &'tr Value) -> Option<&'tr Value> {
-        let mut target = Some(v);
-        for p in &self.paths {
-            if target.is_none() {
-                return None;
-            }
-            if p.is_empty() {
-                continue;
-            }
-            match target.unwrap() {
-                Value::Table(table) => { target = table.get(p); },
-                Value::Array(array) => {
-                    if let Ok(index) = p.parse::<usize>() {
-                        target = array.get(index); 
-                    }
-                },
-                _ => { return None; }
-            }
-        }
-        return target;
-    }
-
-    /// Resolve path readonly for mutable `toml::Value`.
-    /// Bug: if some table key is all numerical char, would mistake as array index.
-    fn apply_mut<'tr>(&self, v: &'tr mut Value) -> Option<&'tr mut Value> {
-        let mut target = Some(v);
-        for p in &self.paths {
-            if target.is_none() {
-                return None;
-            }
-            if p.is_empty() {
-                continue;
-            }
-            match p.parse::<usize>() {
-                Ok(index) => { target = target.unwrap().get_mut(index); },
-                Err(_) => { target = target.unwrap().get_mut(p); },
-            }
-        }
-        return target;
-    }
+/// Apply `#[cfg(feature = "std")]` to each item, to gate the `std` layer as a whole.
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
 }
 
-/// Type trait that can build `PathSegment` from.
-trait PathBuilder {
-    fn build_path(&self) -> PathSegment {
-        PathSegment { paths: Vec::new() }
-    }
-}
+// modules with `macro_export` macro are out of `cfg_std!`,
+// as such macro in macro-expanded module cannot be used by path in this crate.
+#[cfg(feature = "std")]
+mod segment;
+#[cfg(feature = "std")]
+mod table;
+#[cfg(feature = "std")]
+mod compare;
 
-/// split string to get path segment vector.
-impl PathBuilder for &str {
-    fn build_path(&self) -> PathSegment {
-        let paths = self
-            .split(|c| c == '/' || c == '.')
-            .map(|s| s.to_string())
-            .collect();
-        PathSegment { paths }
-    }
-}
+cfg_std! {
+    use tree::{PathBuilder, join_key, step, step_mut};
 
-/// usize index only act path on it's own, but cannot split to more path segment.
-impl PathBuilder for usize {}
+    mod pointer;
+    pub use pointer::{PathOperator, TomlPtr, TomlPtrMut};
 
-/// Provide toml pointer to supported operator overload.
-pub trait PathOperator
-{
-    /// Construct immutable toml pointer to some initial node.
-    fn path<'tr>(&'tr self) -> TomlPtr<'tr>;
+    pub use segment::{Segment, StaticPath};
 
-    /// Construct immutable toml pointer and move it follwoing sub path.
-    fn pathto<'tr>(&'tr self, p: &str) -> TomlPtr<'tr>;
+    /// Re-export `toml`, so that code generated by `TomlView` need not depend on it directly.
+    pub use toml;
 
-    /// Construct mutable toml pointer to some initial node.
-    fn path_mut<'tr>(&'tr mut self) -> TomlPtrMut<'tr>;
+    mod compiled;
+    pub use compiled::CompiledPath;
 
-    /// Construct mutable toml pointer and move it follwoing sub path.
-    fn pathto_mut<'tr>(&'tr mut self, p: &str) -> TomlPtrMut<'tr>;
-}
+    mod array;
 
-/// Create toml pointer directely from `toml::Value`.
-impl PathOperator for Value
-{
-    fn path<'tr>(&'tr self) -> TomlPtr<'tr> {
-        TomlPtr::path(self)
-    }
-    fn pathto<'tr>(&'tr self, p: &str) -> TomlPtr<'tr> {
-        let valop = p.build_path().apply(self);
-        TomlPtr { valop }
-    }
-
-    fn path_mut<'tr>(&'tr mut self) -> TomlPtrMut<'tr> {
-        TomlPtrMut::path(self)
-    }
-    fn pathto_mut<'tr>(&'tr mut self, p: &str) -> TomlPtrMut<'tr> {
-        let valop = p.build_path().apply_mut(self);
-        TomlPtrMut { valop }
-    }
-}
+    mod conditional;
+    pub use conditional::fill_defaults;
 
-/// Wrapper pointer to `toml::Value` for operator overload.
-/// Must refer to an existed toml tree, `Option::None` to refer non-exist node.
-#[derive(Copy, Clone)]
-pub struct TomlPtr<'tr> {
-    valop: Option<&'tr Value>,
-}
+    mod transaction;
+    pub use transaction::{Transaction, TxError, transaction};
 
-impl<'tr> TomlPtr<'tr> {
-    /// As constructor, to build path operand object from a `toml::Value` node.
-    pub fn path(v: &'tr Value) -> Self {
-        Self { valop: Some(v) }
-    }
-    
-    /// As unwrapper, to get the underling `Option<&toml::Value>`.
-    pub fn unpath(&self) -> &Option<&'tr Value> {
-        &self.valop
-    }
-}
+    pub use table::Ser;
 
-/// Overload `!` operator to test the pointer is invalid.
-impl<'tr> Not for TomlPtr<'tr> {
-    type Output = bool;
-    fn not(self) -> Self::Output {
-        self.valop.is_none()
-    }
-}
+    mod owned;
+    pub use owned::{TomlDoc, TomlCursor};
 
-/// Overload `*` deref operator to treate pointer as `Option<&toml::Value>`.
-impl<'tr> Deref for TomlPtr<'tr>
-{
-    type Target = Option<&'tr Value>;
-    fn deref(&self) -> &Self::Target {
-        self.unpath()
-    }
-}
+    mod format;
+    pub use format::{FormatOptions, ArrayOfTables, format_toml, format_str, check_format};
 
-/// Path operator `/`, visit sub-node by string key for table or index for array.
-/// Can chained as `tomlptr / "path" / "to" / "node"` or `tomlptr / "path/to/node"`.
-impl<'tr, Rhs> Div<Rhs> for TomlPtr<'tr>
-where Rhs: PathBuilder + Index + Copy
-{
-    type Output = Self;
-    fn div(self, rhs: Rhs) -> Self::Output {
-        TomlPtr { valop: path(self.valop, rhs) }
-    }
-}
+    mod convert;
+    pub use convert::{Format, ConvertError, Loss, LossKind, Converted};
+    pub use convert::{to_json, from_json, to_yaml, from_yaml, to_ini, from_ini, read_as, write_as, write_subtree};
 
-// pipe operator, get primitive scalar value for leaf node in toml tree.
-// return rhs as default if the node is mistype.
-// support | &str, String, i64, f64, bool,
-// not support datetime type of toml.
-// Note: pipe operator(|) is the vertical form of path operator(/),
-// and usually stand on the end of path chain.
-// eg. `let scalar = toml.path() / "path" / "to" / "leaf" | "default-value"; `
-
-/// Pipe operator `|` with `String`, to get value from string node, 
-/// or return `rhs` as default value if pointer is invalid or type mistach.
-/// Note that the `rhs` string would be moved.
-impl<'tr> BitOr<String> for TomlPtr<'tr>
-{
-    type Output = String;
-    fn bitor(self, rhs: String) -> Self::Output {
-        if self.valop.is_none() {
-            return rhs;
-        }
-        match self.valop.unwrap().as_str() {
-            Some(s) => s.to_string(),
-            None => rhs
-        }
-    }
-}
+    pub use compare::{EqOptions, Mismatch, semantic_eq};
 
-/// Pipe operator `|` with string literal, to get string value or `rhs` as default.
-impl<'tr> BitOr<&'static str> for TomlPtr<'tr>
-{
-    type Output = &'tr str;
-    fn bitor(self, rhs: &'static str) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_str().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
+    mod redact;
+    pub use redact::{Redactor, Redacted};
 
-/// Pipe operator to get integer value or `rhs` as default.
-impl<'tr> BitOr<i64> for TomlPtr<'tr>
-{
-    type Output = i64;
-    fn bitor(self, rhs: i64) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_integer().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
+    mod shell;
+    pub use shell::{Shell, ShellError};
 
-/// Pipe operator to get float value or `rhs` as default.
-impl<'tr> BitOr<f64> for TomlPtr<'tr>
-{
-    type Output = f64;
-    fn bitor(self, rhs: f64) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_float().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
+    mod span;
+    pub use span::{Span, SourceMap, Diagnostic};
 
-/// Pipe operator to get bool value or `rhs` as default.
-impl<'tr> BitOr<bool> for TomlPtr<'tr>
-{
-    type Output = bool;
-    fn bitor(self, rhs: bool) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_bool().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
+    mod lazy;
+    pub use lazy::LazyToml;
 
-/// Mutable version of pointer wrapper of `toml::Value` for operator overload.
-/// Must refer to existed toml tree, `Option::None` to refer non-exist node.
-/// Note that mutable reference don't support copy.
-pub struct TomlPtrMut<'tr> {
-    valop: Option<&'tr mut Value>,
-}
+    mod flatten;
+    pub use flatten::{FlattenError, flatten, unflatten};
 
-impl<'tr> TomlPtrMut<'tr> {
-    /// As constructor, to build path operand object from a `toml::Value` node.
-    pub fn path(v: &'tr mut Value) -> Self {
-        Self { valop: Some(v) }
-    }
-
-    /// As unwrapper, to get the underling `Option<&mut toml::Value>`.
-    pub fn unpath(&self) -> &Option<&'tr mut Value> {
-        &self.valop
-    }
-
-    /// Assign any supported value to toml.
-    /// But canno overload operator=, will choose <<= instead.
-    pub fn assign<T>(&mut self, rhs: T) where Value: From<T> {
-        if let Some(ref mut v) = self.valop {
-            **v = Value::from(rhs);
-        }
-    }
-
-    /// Construct new null pointer.
-    fn none() -> Self {
-        Self { valop: None }
-    }
-
-    /// Put a value to toml and return pointer to it.
-    fn put_val<T>(v: &'tr mut Value, rhs: T) -> Self
-    where Value: From<T>
-    {
-        *v = Value::from(rhs);
-        Self::path(v)
-    }
-
-    /// Put value to string toml node pointer, would invalidate it when type mismatch.
-    /// Implement for << String and << &str.
-    fn put_string(&mut self, rhs: String) -> Self {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_str() {
-            return Self::put_val(v, rhs);
-        }
-        return Self::none();
-    }
-
-    /// Implement for << i64.
-    fn put_integer(&mut self, rhs: i64) -> Self {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_integer() {
-            return Self::put_val(v, rhs);
-        }
-        return Self::none();
-    }
-
-    /// Implement for << f64.
-    fn put_float(&mut self, rhs: f64) -> Self {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_float() {
-            return Self::put_val(v, rhs);
-        }
-        return Self::none();
-    }
-
-    /// Implement for << bool.
-    fn put_bool(&mut self, rhs: bool) -> Self {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_bool() {
-            return Self::put_val(v, rhs);
-        }
-        return Self::none();
-    }
-
-    /// Implment for table << (key, val) pair.
-    fn push_table<K: ToString, T>(&mut self, key: K, val: T) -> Self
-    where Value: From<T>
-    {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_table() {
-            v.as_table_mut().unwrap().insert(key.to_string(), Value::from(val));
-            return Self::path(v);
-        }
-        return Self::none();
-    }
-
-    /// Implment for array << (val, ) << [item] .
-    fn push_array<T>(&mut self, val: T) -> Self
-    where Value: From<T>
-    {
-        if self.valop.is_none() {
-            return Self::none();
-        }
-        let v = self.valop.take().unwrap();
-        if v.is_array() {
-            v.as_array_mut().unwrap().push(Value::from(val));
-            return Self::path(v);
-        }
-        return Self::none();
-    }
-}
+    mod store;
+    pub use store::{TomlStore, StoreOptions, StoreError};
 
-/// Overload `!` operator to test the pointer is invalid.
-impl<'tr> Not for TomlPtrMut<'tr> {
-    type Output = bool;
-    fn not(self) -> Self::Output {
-        self.valop.is_none()
-    }
-}
+    mod manifest;
+    pub use manifest::{CargoManifest, Bump, DepKind, Dependency, ManifestError};
 
-/// Overload `*` deref operator to treate pointer as `Option<&mut toml::Value>`.
-impl<'tr> Deref for TomlPtrMut<'tr> {
-    type Target = Option<&'tr mut Value>;
-    fn deref(&self) -> &Self::Target {
-        &self.valop
-    }
-}
+    mod template;
+    pub use template::{Template, TemplateError, render_str};
 
-/// Overload `*` deref operator to treate pointer as `Option<&mut toml::Value>`.
-impl<'tr> DerefMut for TomlPtrMut<'tr> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.valop
-    }
-}
+    mod units;
+    pub use units::{ByteSize, UnitError, UnitValue, parse_duration, format_duration};
 
-/// Path operator `/`, visit sub-node by string key for table or index for array.
-/// Can chained as `tomlptr / "path" / "to" / "node"` or `tomlptr / "path/to/node"`.
-/// Hope to change the node it point to.
-impl<'tr, Rhs> Div<Rhs> for TomlPtrMut<'tr>
-where Rhs: PathBuilder + Index + Copy
-{
-    type Output = Self;
-
-    fn div(self, rhs: Rhs) -> Self::Output {
-        TomlPtrMut { valop: path_mut(self.valop, rhs) }
-    }
-}
+    mod slicing;
+    pub use slicing::{Slice, SliceOperator};
 
-/// Pipe operator `|` with `String`, to get value from string node, 
-/// or return `rhs` as default value if pointer is invalid or type mistach.
-/// Note that the `rhs` string , as well as the pointer itself would be moved.
-impl<'tr> BitOr<String> for TomlPtrMut<'tr>
-{
-    type Output = String;
-    fn bitor(self, rhs: String) -> Self::Output {
-        if self.valop.is_none() {
-            return rhs;
-        }
-        match self.valop.unwrap().as_str() {
-            Some(s) => s.to_string(),
-            None => rhs
-        }
-    }
-}
+    mod layered;
+    pub use layered::{LayeredConfig, Origin, LayerError};
 
-/// Pipe operator `|` with string literal, to get string value or `rhs` as default.
-impl<'tr> BitOr<&'static str> for TomlPtrMut<'tr>
-{
-    type Output = &'tr str;
-    fn bitor(self, rhs: &'static str) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_str().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
-
-/// Pipe operator to get integer value or `rhs` as default.
-impl<'tr> BitOr<i64> for TomlPtrMut<'tr>
-{
-    type Output = i64;
-    fn bitor(self, rhs: i64) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_integer().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
-
-/// Pipe operator to get float value or `rhs` as default.
-impl<'tr> BitOr<f64> for TomlPtrMut<'tr>
-{
-    type Output = f64;
-    fn bitor(self, rhs: f64) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_float().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
-
-/// Pipe operator to get bool value or `rhs` as default.
-impl<'tr> BitOr<bool> for TomlPtrMut<'tr>
-{
-    type Output = bool;
-    fn bitor(self, rhs: bool) -> Self::Output {
-        match self.valop {
-            Some(v) => v.as_bool().unwrap_or(rhs),
-            None => rhs,
-        }
-    }
-}
-
-/// Operator `<<` to put a string into toml leaf node.
-/// While the data type mismatch the node, set self pointer to `None`.
-impl<'tr> Shl<&str> for TomlPtrMut<'tr> {
-    type Output = Self;
-    fn shl(mut self, rhs: &str) -> Self::Output {
-        self.put_string(rhs.to_string())
-    }
-}
-
-/// Operator `<<` to put and move a string into toml leaf node.
-/// While the data type mismatch the node, set self pointer to `None`.
-impl<'tr> Shl<String> for TomlPtrMut<'tr> {
-    type Output = Self;
-    fn shl(mut self, rhs: String) -> Self::Output {
-        self.put_string(rhs)
-    }
-}
-
-/// Operator `<<` to put a integer value into toml leaf node.
-/// While the data type mismatch the node, set self pointer to `None`.
-impl<'tr> Shl<i64> for TomlPtrMut<'tr> {
-    type Output = Self;
-    fn shl(mut self, rhs: i64) -> Self::Output {
-        self.put_integer(rhs)
-    }
-}
-
-/// Operator `<<` to put a float value into toml leaf node.
-/// While the data type mismatch the node, set self pointer to `None`.
-impl<'tr> Shl<f64> for TomlPtrMut<'tr> {
-    type Output = Self;
-    fn shl(mut self, rhs: f64) -> Self::Output {
-        self.put_float(rhs)
-    }
-}
-
-/// Operator `<<` to put a bool value into toml leaf node.
-/// While the data type mismatch the node, set self pointer to `None`.
-impl<'tr> Shl<bool> for TomlPtrMut<'tr> {
-    type Output = Self;
-    fn shl(mut self, rhs: bool) -> Self::Output {
-        self.put_bool(rhs)
-    }
-}
-
-/// Operator `<<` to push key-value pair (tuple) into toml table.
-/// eg: `toml/table/node << (k, v)` where the k v will be moved.
-impl<'tr, K: ToString, T> Shl<(K, T)> for TomlPtrMut<'tr> where Value: From<T>
-{
-    type Output = Self;
-    fn shl(mut self, rhs: (K, T)) -> Self::Output {
-        self.push_table(rhs.0, rhs.1)
-    }
-}
-
-/// Operator `<<` to push one value tuple into toml array.
-/// eg: `toml/array/node << (v,)`.
-/// Note that use single tuple to distinguish with pushing scalar to leaf node.
-impl<'tr, T> Shl<(T,)> for TomlPtrMut<'tr> where Value: From<T>
-{
-    type Output = Self;
-    fn shl(mut self, rhs: (T,)) -> Self::Output {
-        self.push_array(rhs.0)
-    }
-}
+    mod watched;
+    pub use watched::{WatchedConfig, WatchError, changed_paths};
 
-/// Operator `<<` to push one item to toml array.
-/// eg: `toml/array/node << [v1]`
-impl<'tr, T: Copy> Shl<[T;1]> for TomlPtrMut<'tr> where Value: From<T>
-{
-    type Output = Self;
-    fn shl(mut self, rhs: [T;1]) -> Self::Output {
-        self.push_array(rhs[0])
-    }
-}
-
-/// Operator `<<` to push a slice to toml array.
-/// eg: `toml/array/node << &[v1, v2, v3, ...][..]`
-impl<'tr, T: Copy> Shl<&[T]> for TomlPtrMut<'tr> where Value: From<T>
-{
-    type Output = Self;
-    fn shl(mut self, rhs: &[T]) -> Self::Output {
-        for item in rhs {
-            self = self.push_array(*item);
-        }
-        self
-    }
-}
+    #[cfg(test)]
+    mod tests; // { move to tests.rs }
 
-/// Operator `<<=` re-assign to an node unconditionally, may change it data type.
-/// Note donot use chained `<<=` as `<<` can because `<<=` is right associated.
-impl<'tr, T> ShlAssign<T> for TomlPtrMut<'tr> where Value: From<T> 
-{
-    fn shl_assign(&mut self, rhs: T) {
-        self.assign(rhs);
-    }
+    #[cfg(test)]
+    mod proptests;
 }
 
-#[cfg(test)]
-mod tests; // { move to tests.rs }
+/// Derive typed view of toml layout, see the `tomloper-derive` crate for the attributes.
+///
+/// Only struct with named fields can derive it:
+/// ```compile_fail
+/// #[derive(tomloper::TomlView)]
+/// enum Mode {
+///     Fast,
+///     Slow,
+/// }
+/// ```
+///
+/// And the fields must be `i64`, `f64`, `bool`, `&str`, `String`, `TomlPtr` or `Option` of them:
+/// ```compile_fail
+/// #[derive(tomloper::TomlView)]
+/// struct Limits {
+///     #[toml(path = "limits.max")]
+///     max: u32,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use tomloper_derive::TomlView;
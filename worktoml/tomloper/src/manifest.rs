@@ -0,0 +1,310 @@
+//! Typed helpers to edit Cargo manifest, built on the path operators.
+//!
+//! `CargoManifest` is implemented for `toml::Value`, so it works on parsed `Cargo.toml`,
+//! inside `transaction()` or `TomlStore::update()`. Dependency in short form `name = "1.0"`
+//! is kept short as long as it has nothing other than version.
+//!
+//! ```rust
+//! use tomloper::{Bump, CargoManifest, DepKind, Dependency, PathOperator};
+//! let mut v: toml::Value = "[package]\nname = \"app\"\nversion = \"0.1.9\"\n\n[dependencies]\nserde = \"1.0\"\n".parse().unwrap();
+//!
+//! assert_eq!(v.bump_version(Bump::Minor).unwrap(), "0.2.0");
+//! assert_eq!(v.path() / "package" / "version" | "", "0.2.0");
+//!
+//! let dep = Dependency { name: "tokio".into(), version: Some("1".into()), features: vec!["rt".into()], ..Default::default() };
+//! v.add_dependency(DepKind::Normal, &dep).unwrap();
+//! assert_eq!(v.pathto("dependencies/tokio/features/0") | "", "rt");
+//!
+//! v.use_path(DepKind::Normal, "serde", "../serde").unwrap();
+//! assert_eq!(v.pathto("dependencies/serde/path") | "", "../serde");
+//! assert_eq!(v.remove_dependency(DepKind::Normal, "tokio"), true);
+//! ```
+
+use std::fmt;
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::{PathOperator, TomlPtrMut};
+
+/// Which part of the version to bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+/// Which dependency table to edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepKind {
+    /// `[dependencies]`
+    #[default]
+    Normal,
+    /// `[dev-dependencies]`
+    Dev,
+    /// `[build-dependencies]`
+    Build,
+    /// `[workspace.dependencies]`
+    Workspace,
+}
+
+impl DepKind {
+    /// Path of the dependency table.
+    pub fn table(self) -> &'static str {
+        match self {
+            DepKind::Normal => "dependencies",
+            DepKind::Dev => "dev-dependencies",
+            DepKind::Build => "build-dependencies",
+            DepKind::Workspace => "workspace.dependencies",
+        }
+    }
+}
+
+/// Dependency spec, the common keys only.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub version: Option<String>,
+    pub path: Option<String>,
+    pub features: Vec<String>,
+    pub optional: bool,
+}
+
+/// Error of manifest edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    /// Neither `package.version` nor `workspace.package.version` found.
+    NoVersion,
+    /// Version is not `major.minor.patch`.
+    BadVersion(String),
+    /// No such dependency in the table.
+    NoDependency(String),
+    /// The node to edit is not table, with its dotted path.
+    NotTable(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::NoVersion => write!(f, "no package version"),
+            ManifestError::BadVersion(v) => write!(f, "bad version: {}", v),
+            ManifestError::NoDependency(name) => write!(f, "no such dependency: {}", name),
+            ManifestError::NotTable(p) => write!(f, "not a table: {}", p),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// Edit operations on Cargo manifest.
+pub trait CargoManifest {
+    /// Version of package, or of workspace package if it is inherited.
+    fn package_version(&self) -> Option<&str>;
+
+    /// Bump the package version and return the new one.
+    /// Pre-release and build suffix are dropped.
+    fn bump_version(&mut self, bump: Bump) -> Result<String, ManifestError>;
+
+    /// All dependencies in the table, sorted by name.
+    fn dependencies(&self, kind: DepKind) -> Vec<Dependency>;
+
+    /// Get dependency by name.
+    fn dependency(&self, kind: DepKind, name: &str) -> Option<Dependency>;
+
+    /// Add dependency, or update the existed one: version and path are replaced if given,
+    /// features are merged, and other keys such as `default-features` are kept.
+    fn add_dependency(&mut self, kind: DepKind, dep: &Dependency) -> Result<(), ManifestError>;
+
+    /// Remove dependency, return false if not found.
+    fn remove_dependency(&mut self, kind: DepKind, name: &str) -> bool;
+
+    /// Members of workspace as written, glob is not expanded.
+    fn workspace_members(&self) -> Vec<String>;
+
+    /// Switch dependency to local path, removing its version and git source.
+    fn use_path(&mut self, kind: DepKind, name: &str, path: &str) -> Result<(), ManifestError>;
+
+    /// Switch dependency to registry version, removing its path and git source.
+    fn use_version(&mut self, kind: DepKind, name: &str, version: &str) -> Result<(), ManifestError>;
+}
+
+/// Keys of dependency source, which are replaced when switch source.
+const SOURCE_KEYS: [&str; 7] = ["version", "path", "git", "branch", "tag", "rev", "workspace"];
+
+impl CargoManifest for Value {
+    fn package_version(&self) -> Option<&str> {
+        let p = version_path(self)?;
+        self.pathto(p).and_then(|v| v.as_str())
+    }
+
+    fn bump_version(&mut self, bump: Bump) -> Result<String, ManifestError> {
+        let Some(p) = version_path(self) else {
+            return Err(ManifestError::NoVersion);
+        };
+        let old = self.pathto(p) | "";
+        let new = bump_text(old, bump).ok_or_else(|| ManifestError::BadVersion(old.to_string()))?;
+        if !(self.pathto_mut(p) << new.as_str()) {
+            return Err(ManifestError::NoVersion);
+        }
+        Ok(new)
+    }
+
+    fn dependencies(&self, kind: DepKind) -> Vec<Dependency> {
+        let Some(table) = self.pathto(kind.table()).and_then(|v| v.as_table()) else {
+            return Vec::new();
+        };
+        table.iter().map(|(name, spec)| read_dependency(name, spec)).collect()
+    }
+
+    fn dependency(&self, kind: DepKind, name: &str) -> Option<Dependency> {
+        let spec = self.pathto(kind.table()).and_then(|v| v.get(name))?;
+        Some(read_dependency(name, spec))
+    }
+
+    fn add_dependency(&mut self, kind: DepKind, dep: &Dependency) -> Result<(), ManifestError> {
+        let mut spec = match self.pathto(kind.table()) / dep.name.as_str() {
+            ptr if !ptr => Table::new(),
+            ptr => spec_table(ptr.unwrap()),
+        };
+        if dep.version.is_some() || dep.path.is_some() {
+            spec.retain(|k, _| !SOURCE_KEYS.contains(&k));
+        }
+        if let Some(version) = &dep.version {
+            spec.insert("version".to_string(), Value::from(version.as_str()));
+        }
+        if let Some(path) = &dep.path {
+            spec.insert("path".to_string(), Value::from(path.as_str()));
+        }
+        if !dep.features.is_empty() {
+            let mut features = match spec.remove("features") {
+                Some(Value::Array(features)) => features,
+                _ => Vec::new(),
+            };
+            for feature in &dep.features {
+                let feature = Value::from(feature.as_str());
+                if !features.contains(&feature) {
+                    features.push(feature);
+                }
+            }
+            spec.insert("features".to_string(), Value::Array(features));
+        }
+        if dep.optional {
+            spec.insert("optional".to_string(), Value::Boolean(true));
+        }
+        put_spec(table_mut(self, kind.table())?, &dep.name, spec, kind)
+    }
+
+    fn remove_dependency(&mut self, kind: DepKind, name: &str) -> bool {
+        let table = self.pathto_mut(kind.table());
+        match table.valop.and_then(|v| v.as_table_mut()) {
+            Some(table) => table.remove(name).is_some(),
+            None => false,
+        }
+    }
+
+    fn workspace_members(&self) -> Vec<String> {
+        let Some(members) = self.pathto("workspace/members").and_then(|v| v.as_array()) else {
+            return Vec::new();
+        };
+        members.iter().filter_map(|m| m.as_str()).map(|m| m.to_string()).collect()
+    }
+
+    fn use_path(&mut self, kind: DepKind, name: &str, path: &str) -> Result<(), ManifestError> {
+        switch_source(self, kind, name, "path", path)
+    }
+
+    fn use_version(&mut self, kind: DepKind, name: &str, version: &str) -> Result<(), ManifestError> {
+        switch_source(self, kind, name, "version", version)
+    }
+}
+
+/// Path of the version to bump, `None` if not found or inherited without workspace.
+fn version_path(v: &Value) -> Option<&'static str> {
+    if v.pathto("package/version").is_some_and(|v| v.is_str()) {
+        return Some("package/version");
+    }
+    if v.pathto("workspace/package/version").is_some_and(|v| v.is_str()) {
+        return Some("workspace/package/version");
+    }
+    return None;
+}
+
+fn bump_text(version: &str, bump: Bump) -> Option<String> {
+    let core = version.split(['-', '+']).next()?;
+    let parts: Vec<u64> = core.split('.').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    let [major, minor, patch] = parts[..] else {
+        return None;
+    };
+    let (major, minor, patch) = match bump {
+        Bump::Major => (major + 1, 0, 0),
+        Bump::Minor => (major, minor + 1, 0),
+        Bump::Patch => (major, minor, patch + 1),
+    };
+    Some(format!("{}.{}.{}", major, minor, patch))
+}
+
+fn read_dependency(name: &str, spec: &Value) -> Dependency {
+    let spec = spec_table(spec);
+    let text = |key: &str| spec.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let features = spec.get("features").and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|f| f.as_str()).map(|f| f.to_string()).collect())
+        .unwrap_or_default();
+    Dependency {
+        name: name.to_string(),
+        version: text("version"),
+        path: text("path"),
+        features,
+        optional: spec.get("optional").and_then(|v| v.as_bool()).unwrap_or(false),
+    }
+}
+
+/// Dependency spec as table, expand short form `"1.0"` to `{ version = "1.0" }`.
+fn spec_table(spec: &Value) -> Table {
+    match spec {
+        Value::Table(table) => table.clone(),
+        Value::String(version) => {
+            let mut table = Table::new();
+            table.insert("version".to_string(), Value::from(version.as_str()));
+            table
+        },
+        _ => Table::new(),
+    }
+}
+
+/// Pointer to table at dotted path, create the missing ones.
+fn table_mut<'tr>(v: &'tr mut Value, p: &str) -> Result<TomlPtrMut<'tr>, ManifestError> {
+    let mut ptr = v.path_mut();
+    for key in p.split('.') {
+        ptr = ptr.set_default(key, Table::new()) / key;
+    }
+    if !ptr.as_deref().is_some_and(|v| v.is_table()) {
+        return Err(ManifestError::NotTable(p.to_string()));
+    }
+    Ok(ptr)
+}
+
+/// Put spec into dependency table, in short form if only version.
+fn put_spec(table: TomlPtrMut, name: &str, spec: Table, kind: DepKind) -> Result<(), ManifestError> {
+    let ptr = match spec.get("version").and_then(|v| v.as_str()) {
+        Some(version) if spec.len() == 1 => table << (name, version),
+        _ => table << (name, spec),
+    };
+    if !ptr {
+        return Err(ManifestError::NotTable(kind.table().to_string()));
+    }
+    Ok(())
+}
+
+fn switch_source(v: &mut Value, kind: DepKind, name: &str, key: &str, source: &str) -> Result<(), ManifestError> {
+    let Some(spec) = v.pathto(kind.table()).and_then(|t| t.get(name)) else {
+        return Err(ManifestError::NoDependency(name.to_string()));
+    };
+    let mut spec = spec_table(spec);
+    spec.retain(|k, _| !SOURCE_KEYS.contains(&k));
+    spec.insert(key.to_string(), Value::from(source));
+    put_spec(table_mut(v, kind.table())?, name, spec, kind)
+}
+
+#[cfg(test)]
+mod tests;
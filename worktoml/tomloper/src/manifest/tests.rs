@@ -0,0 +1,119 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+
+/// Fixed package manifest, not the real one of this crate that may change.
+const TEST_MANIFEST: &str = r#"
+[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+serde = "1.0"
+toml = "0.8"
+rustyline = { version = "14", optional = true }
+
+[dev-dependencies]
+serde = { version = "1.0", features = ["derive"] }
+
+[features]
+repl = ["rustyline"]
+"#;
+
+/// Fixed virtual workspace manifest.
+const TEST_WORKSPACE: &str = r#"
+[workspace]
+members = ["app", "app-derive"]
+
+[workspace.package]
+version = "0.1.0"
+"#;
+
+fn load_test_manifest() -> Value
+{
+    let v: Value = TEST_MANIFEST.parse().unwrap();
+    return v;
+}
+
+#[test]
+fn version_test() {
+    let mut v = load_test_manifest();
+    assert_eq!(v.package_version(), Some("0.1.0"));
+    assert_eq!(v.bump_version(Bump::Patch).unwrap(), "0.1.1");
+    assert_eq!(v.bump_version(Bump::Minor).unwrap(), "0.2.0");
+    assert_eq!(v.bump_version(Bump::Major).unwrap(), "1.0.0");
+    assert_eq!(v.package_version(), Some("1.0.0"));
+
+    let mut v: Value = "[package]\nversion = \"2.0.0-rc.1\"".parse().unwrap();
+    assert_eq!(v.bump_version(Bump::Patch).unwrap(), "2.0.1");
+
+    let mut v: Value = "[package]\nversion = \"latest\"".parse().unwrap();
+    assert_eq!(v.bump_version(Bump::Patch), Err(ManifestError::BadVersion("latest".to_string())));
+
+    let mut v: Value = "[package]\nversion.workspace = true\n[workspace.package]\nversion = \"0.3.0\"".parse().unwrap();
+    assert_eq!(v.bump_version(Bump::Minor).unwrap(), "0.4.0");
+    assert_eq!(v.pathto("package/version/workspace") | false, true);
+
+    let mut v: Value = "[package]\nname = \"app\"".parse().unwrap();
+    assert_eq!(v.bump_version(Bump::Patch), Err(ManifestError::NoVersion));
+}
+
+#[test]
+fn dependency_test() {
+    let mut v = load_test_manifest();
+    let serde = v.dependency(DepKind::Normal, "serde").unwrap();
+    assert_eq!(serde.version.as_deref(), Some("1.0"));
+    let rustyline = v.dependency(DepKind::Normal, "rustyline").unwrap();
+    assert_eq!(rustyline.optional, true);
+    let serde_dev = v.dependency(DepKind::Dev, "serde").unwrap();
+    assert_eq!(serde_dev.features, vec!["derive"]);
+    assert_eq!(v.dependencies(DepKind::Build).is_empty(), true);
+
+    let dep = Dependency { name: "serde".into(), features: vec!["derive".into(), "rc".into()], ..Default::default() };
+    v.add_dependency(DepKind::Dev, &dep).unwrap();
+    assert_eq!(v.dependency(DepKind::Dev, "serde").unwrap().features, vec!["derive", "rc"]);
+    assert_eq!(v.pathto("dev-dependencies/serde/version") | "", "1.0");
+
+    let dep = Dependency { name: "cc".into(), version: Some("1.0".into()), ..Default::default() };
+    v.add_dependency(DepKind::Build, &dep).unwrap();
+    assert_eq!(v.pathto("build-dependencies/cc") | "", "1.0");
+
+    let dep = Dependency { name: "anyhow".into(), version: Some("1".into()), ..Default::default() };
+    v.add_dependency(DepKind::Workspace, &dep).unwrap();
+    assert_eq!(v.pathto("workspace/dependencies/anyhow") | "", "1");
+
+    assert_eq!(v.remove_dependency(DepKind::Build, "cc"), true);
+    assert_eq!(v.remove_dependency(DepKind::Build, "cc"), false);
+    assert_eq!(v.remove_dependency(DepKind::Workspace, "none"), false);
+}
+
+#[test]
+fn switch_source_test() {
+    let mut v: Value = "[dependencies]\nfoo = { version = \"0.2\", default-features = false }\nbar = \"1\"".parse().unwrap();
+    v.use_path(DepKind::Normal, "foo", "../foo").unwrap();
+    assert_eq!(v.pathto("dependencies/foo/path") | "", "../foo");
+    assert_eq!(v.pathto("dependencies/foo/default-features") | true, false);
+    assert_eq!(!v.pathto("dependencies/foo/version"), true);
+
+    v.use_version(DepKind::Normal, "foo", "0.3").unwrap();
+    assert_eq!(!v.pathto("dependencies/foo/path"), true);
+    assert_eq!(v.pathto("dependencies/foo/version") | "", "0.3");
+
+    v.use_path(DepKind::Normal, "bar", "../bar").unwrap();
+    v.use_version(DepKind::Normal, "bar", "2").unwrap();
+    assert_eq!(v.pathto("dependencies/bar") | "", "2");
+
+    assert_eq!(v.use_path(DepKind::Normal, "baz", "../baz"), Err(ManifestError::NoDependency("baz".to_string())));
+}
+
+#[test]
+fn workspace_test() {
+    let v: Value = TEST_WORKSPACE.parse().unwrap();
+    assert_eq!(v.workspace_members(), vec!["app", "app-derive"]);
+    assert_eq!(load_test_manifest().workspace_members().is_empty(), true);
+
+    let mut v: Value = "dependencies = 1".parse().unwrap();
+    let dep = Dependency { name: "cc".into(), version: Some("1.0".into()), ..Default::default() };
+    assert_eq!(v.add_dependency(DepKind::Normal, &dep), Err(ManifestError::NotTable("dependencies".to_string())));
+}
@@ -0,0 +1,252 @@
+//! Owned toml document shared by `Arc`, and owned cursor into it,
+//! which support the same path operator `/` and pipe operator `|` as `TomlPtr`.
+//! Neither borrows the tree, so they can be stored in long-lived struct or sent to threads.
+//! Mutation is copy-on-write, a new snapshot is made while the old one may still be in use.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, TomlDoc};
+//! let mut doc: TomlDoc = "[host]\nport = 8080".parse().unwrap();
+//!
+//! let port = doc.path() / "host" / "port";
+//! let worker = std::thread::spawn(move || port | 0);
+//!
+//! let old = doc.clone();
+//! doc.edit(|v| { let _ = v.path_mut() / "host" / "port" << 9090; });
+//! assert_eq!(worker.join().unwrap(), 8080);
+//! assert_eq!(doc.path() / "host" / "port" | 0, 9090);
+//! assert_eq!(old.path() / "host" / "port" | 0, 8080);
+//! ```
+
+use std::fmt;
+use std::ops::{BitOr, Div, Not};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use toml::Value;
+
+use crate::{CompiledPath, StaticPath};
+use crate::pointer::path;
+use crate::segment::apply_segments;
+
+/// Immutable snapshot of toml tree, cheap to clone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlDoc {
+    root: Arc<Value>,
+}
+
+impl TomlDoc {
+    /// Take owner of a toml tree.
+    pub fn new(v: Value) -> Self {
+        Self { root: Arc::new(v) }
+    }
+
+    /// The underling toml tree.
+    pub fn value(&self) -> &Value {
+        &self.root
+    }
+
+    /// Share the underling tree.
+    pub fn snapshot(&self) -> Arc<Value> {
+        Arc::clone(&self.root)
+    }
+
+    /// Construct cursor to the root node.
+    pub fn path(&self) -> TomlCursor {
+        TomlCursor { doc: self.snapshot(), paths: Vec::new() }
+    }
+
+    /// Construct cursor and move it following sub path.
+    pub fn pathto(&self, p: &str) -> TomlCursor {
+        self.path() / p
+    }
+
+    /// Modify the tree in place, it is cloned first if other handle or cursor still share it.
+    pub fn edit<F, R>(&mut self, f: F) -> R
+    where F: FnOnce(&mut Value) -> R
+    {
+        f(Arc::make_mut(&mut self.root))
+    }
+
+    /// Make a modified copy as new snapshot, and keep self unchanged.
+    pub fn with_edit<F>(&self, f: F) -> Self
+    where F: FnOnce(&mut Value)
+    {
+        let mut v = Value::clone(&self.root);
+        f(&mut v);
+        Self::new(v)
+    }
+}
+
+impl From<Value> for TomlDoc {
+    fn from(v: Value) -> Self {
+        Self::new(v)
+    }
+}
+
+/// Share the snapshot such as from `WatchedConfig::load()`.
+impl From<Arc<Value>> for TomlDoc {
+    fn from(root: Arc<Value>) -> Self {
+        Self { root }
+    }
+}
+
+impl FromStr for TomlDoc {
+    type Err = toml::de::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s.parse()?))
+    }
+}
+
+impl fmt::Display for TomlDoc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+/// Path recorded by cursor, resolved in the same way as `TomlPtr` does.
+#[derive(Debug, Clone)]
+enum CursorPath {
+    Key(String),
+    Index(usize),
+    Compiled(CompiledPath),
+    Static(StaticPath),
+}
+
+/// Owned pointer into a `TomlDoc` snapshot, which keep the snapshot alive.
+/// The path is resolved each time when read, may refer non-exist node.
+#[derive(Debug, Clone)]
+pub struct TomlCursor {
+    doc: Arc<Value>,
+    paths: Vec<CursorPath>,
+}
+
+impl TomlCursor {
+    /// Resolve the node it point to.
+    pub fn get(&self) -> Option<&Value> {
+        let mut target = Some(&*self.doc);
+        for p in &self.paths {
+            target = match p {
+                CursorPath::Key(k) => path(target, k.as_str()),
+                CursorPath::Index(i) => path(target, *i),
+                CursorPath::Compiled(c) => target.and_then(|v| c.apply(v)),
+                CursorPath::Static(s) => target.and_then(|v| apply_segments(v, s.segments())),
+            };
+        }
+        return target;
+    }
+
+    /// Test the cursor point to existed node.
+    pub fn is_some(&self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Test the cursor point to non-exist node.
+    pub fn is_none(&self) -> bool {
+        self.get().is_none()
+    }
+
+    /// The snapshot it point into.
+    pub fn doc(&self) -> TomlDoc {
+        TomlDoc::from(Arc::clone(&self.doc))
+    }
+
+    fn push(mut self, p: CursorPath) -> Self {
+        self.paths.push(p);
+        self
+    }
+}
+
+/// Overload `!` operator to test the cursor is invalid.
+impl Not for &TomlCursor {
+    type Output = bool;
+    fn not(self) -> Self::Output {
+        self.is_none()
+    }
+}
+
+/// Overload `!` operator to test the cursor is invalid.
+impl Not for TomlCursor {
+    type Output = bool;
+    fn not(self) -> Self::Output {
+        self.is_none()
+    }
+}
+
+/// Path operator `/` with string path.
+impl Div<&str> for TomlCursor {
+    type Output = Self;
+    fn div(self, rhs: &str) -> Self::Output {
+        self.push(CursorPath::Key(rhs.to_string()))
+    }
+}
+
+/// Path operator `/` with array index.
+impl Div<usize> for TomlCursor {
+    type Output = Self;
+    fn div(self, rhs: usize) -> Self::Output {
+        self.push(CursorPath::Index(rhs))
+    }
+}
+
+/// Path operator `/` with compiled path.
+impl Div<&CompiledPath> for TomlCursor {
+    type Output = Self;
+    fn div(self, rhs: &CompiledPath) -> Self::Output {
+        self.push(CursorPath::Compiled(rhs.clone()))
+    }
+}
+
+/// Path operator `/` with static path.
+impl Div<StaticPath> for TomlCursor {
+    type Output = Self;
+    fn div(self, rhs: StaticPath) -> Self::Output {
+        self.push(CursorPath::Static(rhs))
+    }
+}
+
+/// Pipe operator `|` with string, to get owned string value or `rhs` as default,
+/// because the cursor is moved and cannot lend a `&str`.
+impl BitOr<&str> for TomlCursor {
+    type Output = String;
+    fn bitor(self, rhs: &str) -> Self::Output {
+        self.get().and_then(|v| v.as_str()).unwrap_or(rhs).to_string()
+    }
+}
+
+/// Pipe operator `|` with `String`, to get string value or `rhs` as default.
+impl BitOr<String> for TomlCursor {
+    type Output = String;
+    fn bitor(self, rhs: String) -> Self::Output {
+        match self.get().and_then(|v| v.as_str()) {
+            Some(s) => s.to_string(),
+            None => rhs,
+        }
+    }
+}
+
+/// Pipe operator to get integer value or `rhs` as default.
+impl BitOr<i64> for TomlCursor {
+    type Output = i64;
+    fn bitor(self, rhs: i64) -> Self::Output {
+        self.get().and_then(|v| v.as_integer()).unwrap_or(rhs)
+    }
+}
+
+/// Pipe operator to get float value or `rhs` as default.
+impl BitOr<f64> for TomlCursor {
+    type Output = f64;
+    fn bitor(self, rhs: f64) -> Self::Output {
+        self.get().and_then(|v| v.as_float()).unwrap_or(rhs)
+    }
+}
+
+/// Pipe operator to get bool value or `rhs` as default.
+impl BitOr<bool> for TomlCursor {
+    type Output = bool;
+    fn bitor(self, rhs: bool) -> Self::Output {
+        self.get().and_then(|v| v.as_bool()).unwrap_or(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests;
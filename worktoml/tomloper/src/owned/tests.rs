@@ -0,0 +1,74 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::{PathOperator, toml_path};
+use std::thread;
+use crate::tests::load_test_text;
+
+fn load_test_doc() -> TomlDoc
+{
+    let str_toml = load_test_text();
+    return str_toml.parse().unwrap();
+}
+
+#[test]
+#[allow(clippy::approx_constant)]
+fn cursor_test() {
+    let doc = load_test_doc();
+
+    let host = doc.path() / "host";
+    assert_eq!(host.clone() / "ip" | "", "127.0.1.1");
+    assert_eq!(host.clone() / "port" | 0, 8080);
+    assert_eq!(host.clone() / "protocol" / 1 | "", "udp");
+    assert_eq!(doc.pathto("host/protocol/2") | String::new(), "mmp");
+    assert_eq!(doc.path() / &CompiledPath::new("service.1.name") | "", "serv_2");
+    assert_eq!(doc.path() / toml_path!(misc.float) | 0.0, 3.14);
+    assert_eq!(doc.path() / "misc" / "bool" | false, true);
+
+    let invalid = host.clone() / "no-key" / "more";
+    assert_eq!(!&invalid, true);
+    assert_eq!(invalid.is_none(), true);
+    assert_eq!(invalid | "default", "default");
+    assert_eq!(!host, false);
+}
+
+#[test]
+fn cow_edit_test() {
+    let mut doc = load_test_doc();
+    let port = doc.path() / "host" / "port";
+    let old = doc.clone();
+
+    doc.edit(|v| {
+        let _ = v.path_mut() / "host" / "port" << 9090;
+    });
+    assert_eq!(doc.path() / "host" / "port" | 0, 9090);
+    // cursor and clone hold the old snapshot
+    assert_eq!(port.clone() | 0, 8080);
+    assert_eq!(old.path() / "host" / "port" | 0, 8080);
+    assert_eq!(port.doc(), old);
+
+    let new = doc.with_edit(|v| {
+        let _ = v.path_mut() / "host" << ("debug", true);
+    });
+    assert_eq!(new.path() / "host" / "debug" | false, true);
+    assert_eq!((doc.path() / "host" / "debug").is_none(), true);
+
+    let shared = TomlDoc::from(new.snapshot());
+    assert_eq!(shared, new);
+}
+
+#[test]
+fn thread_share_test() {
+    let mut doc = load_test_doc();
+    let workers: Vec<_> = (0..4).map(|i| {
+        let cursor = doc.path() / "service" / (i % 2);
+        thread::spawn(move || cursor / "name" | "")
+    }).collect();
+
+    doc.edit(|v| {
+        let _ = v.path_mut() / "service" / 0 / "name" << "changed";
+    });
+    let names: Vec<String> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+    assert_eq!(names, vec!["serv_1", "serv_2", "serv_1", "serv_2"]);
+    assert_eq!(doc.pathto("service.0.name") | "", "changed");
+}
@@ -0,0 +1,134 @@
+//! Property tests of path resolution on arbitrary toml trees.
+//! Table keys are generated without dot, slash or leading digit,
+//! which the path syntax cannot express anyway.
+
+use proptest::prelude::*;
+use toml::Value;
+use toml::value::Table;
+
+use crate::*;
+
+fn arb_key() -> impl Strategy<Value = String> {
+    "[a-z_][a-z0-9_-]{0,5}"
+}
+
+fn arb_leaf() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        any::<i64>().prop_map(Value::Integer),
+        (-1e9f64..1e9).prop_map(Value::Float),
+        any::<bool>().prop_map(Value::Boolean),
+        "[a-zA-Z0-9 ]{0,8}".prop_map(Value::String),
+    ]
+}
+
+fn arb_value() -> impl Strategy<Value = Value> {
+    arb_leaf().prop_recursive(4, 48, 5, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..5).prop_map(Value::Array),
+            prop::collection::btree_map(arb_key(), inner, 0..5)
+                .prop_map(|m| Value::Table(m.into_iter().collect::<Table>())),
+        ]
+    })
+}
+
+/// Arbitrary document, the root is always table.
+fn arb_doc() -> impl Strategy<Value = Value> {
+    prop::collection::btree_map(arb_key(), arb_value(), 0..6)
+        .prop_map(|m| Value::Table(m.into_iter().collect::<Table>()))
+}
+
+/// Segments of every node in the tree, including root.
+fn all_paths(v: &Value) -> Vec<Vec<String>> {
+    let mut paths = vec![Vec::new()];
+    let children: Vec<(String, &Value)> = match v {
+        Value::Table(table) => table.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        Value::Array(array) => array.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+        _ => Vec::new(),
+    };
+    for (k, sub) in children {
+        for mut p in all_paths(sub) {
+            p.insert(0, k.clone());
+            paths.push(p);
+        }
+    }
+    return paths;
+}
+
+/// Document with path string of an existed node, joined by slash or dot.
+fn arb_doc_path() -> impl Strategy<Value = (Value, String)> {
+    arb_doc().prop_flat_map(|v| {
+        let paths = all_paths(&v);
+        (Just(v), prop::sample::select(paths), any::<bool>())
+    })
+    .prop_map(|(v, p, slash)| (v, p.join(if slash { "/" } else { "." })))
+}
+
+/// Flat dotted paths with few segments, so that index keys and conflicts are common.
+fn arb_flat() -> impl Strategy<Value = Vec<(String, Value)>> {
+    let path = prop::collection::vec(prop::sample::select(vec!["a", "b", "0", "1"]), 0..4)
+        .prop_map(|p| p.join("."));
+    prop::collection::vec((path, arb_leaf()), 0..6)
+}
+
+/// Path string that may or may not exist, mixed keys and indexes.
+fn arb_path() -> impl Strategy<Value = String> {
+    let segment = prop_oneof![arb_key(), (-6isize..6).prop_map(|i| i.to_string())];
+    (prop::collection::vec(segment, 0..5), any::<bool>())
+        .prop_map(|(p, slash)| p.join(if slash { "/" } else { "." }))
+}
+
+proptest! {
+    #[test]
+    fn apply_same_node((v, p) in arb_doc_path(), q in arb_path(), k in arb_key()) {
+        let child = format!("{}/{}", p, k);
+        for p in [p, q, child] {
+            let segments = p.as_str().build_path();
+            let read = segments.apply(&v).cloned();
+            let mut w = v.clone();
+            let write = segments.apply_mut(&mut w).cloned();
+            prop_assert_eq!(&read, &write, "path {}", p);
+
+            prop_assert_eq!(v.pathto(&p).unpath().cloned(), read.clone());
+            prop_assert_eq!(w.pathto_mut(&p).unpath().as_deref().cloned(), read.clone());
+            prop_assert_eq!(CompiledPath::new(&p).apply(&v).cloned(), read.clone());
+        }
+    }
+
+    #[test]
+    fn flatten_round_trip(v in arb_doc()) {
+        let flat = flatten(&v);
+        for (k, leaf) in &flat {
+            prop_assert_eq!(*v.pathto(k).unpath(), Some(leaf));
+        }
+        prop_assert_eq!(unflatten(flat), Ok(v));
+    }
+
+    #[test]
+    fn unflatten_any_order(flat in arb_flat()) {
+        let built = unflatten(flat.clone());
+        let mut reversed = flat.clone();
+        reversed.reverse();
+        prop_assert_eq!(built.is_ok(), unflatten(reversed).is_ok());
+        // empty input build empty root table, which is flatten as leaf
+        if let (Ok(v), false) = (built, flat.is_empty()) {
+            let expect: std::collections::BTreeMap<String, Value> = flat.into_iter().collect();
+            prop_assert_eq!(flatten(&v), expect);
+        }
+    }
+
+    #[test]
+    fn assign_then_read((v, p) in arb_doc_path(), i in any::<i64>(), s in "[a-z]{0,8}", b in any::<bool>()) {
+        let mut w = v.clone();
+        let mut node = w.pathto_mut(&p);
+        node <<= i;
+        prop_assert_eq!(w.pathto(&p) | 0, i);
+
+        let mut node = w.pathto_mut(&p);
+        node <<= s.as_str();
+        prop_assert_eq!(w.pathto(&p) | "", s.as_str());
+
+        let mut node = w.pathto_mut(&p);
+        node <<= b;
+        prop_assert_eq!(w.pathto(&p) | !b, b);
+    }
+}
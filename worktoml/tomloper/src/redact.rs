@@ -0,0 +1,178 @@
+//! Mask secret leaves in a copy of toml tree before output, such as logging the config.
+//!
+//! The path pattern is split on slash(/) or dot(.) as the path operator does,
+//! `*` match any single key or index, or part of a key like `*_token`,
+//! and `**` match any number of segments.
+//! When a pattern match a table or array, all leaves under it are masked.
+//! Keys are split in the same way, so quoted key `"db.password"` is the same as `db.password`.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, Redactor};
+//! let v: toml::Value = r#"
+//! [db.main]
+//! user = "root"
+//! password = "secret"
+//! token = "abc"
+//! [auth]
+//! password = "secret"
+//! "#.parse().unwrap();
+//!
+//! let redactor = Redactor::new(["**.password", "db.*.token"]);
+//! let safe = redactor.redact(&v);
+//! assert_eq!(safe.path() / "db" / "main" / "password" | "", "***");
+//! assert_eq!(safe.path() / "db" / "main" / "token" | "", "***");
+//! assert_eq!(safe.path() / "db" / "main" / "user" | "", "root");
+//! assert_eq!(safe.path() / "auth" / "password" | "", "***");
+//!
+//! let text = redactor.display(&v).to_string();
+//! assert_eq!(text.contains("secret"), false);
+//! ```
+
+use std::fmt;
+
+use toml::Value;
+
+use crate::{FormatOptions, format_toml, join_key};
+
+/// Set of path patterns to mask.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    patterns: Vec<Vec<String>>,
+    mask: String,
+}
+
+impl Redactor {
+    /// Create redactor with path patterns, masking as `"***"`.
+    pub fn new<I, S>(patterns: I) -> Self
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let patterns = patterns.into_iter()
+            .map(|p| p.as_ref().split(['/', '.']).filter(|s| !s.is_empty()).map(String::from).collect())
+            .collect();
+        Self { patterns, mask: String::from("***") }
+    }
+
+    /// Use other string to replace the masked leaves.
+    pub fn mask(mut self, mask: &str) -> Self {
+        self.mask = mask.to_string();
+        self
+    }
+
+    /// Test if the dotted path match any pattern.
+    pub fn is_match(&self, path: &str) -> bool {
+        let segments: Vec<&str> = path.split(['/', '.']).filter(|s| !s.is_empty()).collect();
+        self.patterns.iter().any(|p| match_path(p, &segments))
+    }
+
+    /// Return a copy with matched leaves masked.
+    pub fn redact(&self, v: &Value) -> Value {
+        let mut copy = v.clone();
+        self.redact_mut(&mut copy);
+        return copy;
+    }
+
+    /// Mask matched leaves in place, return the dotted paths of masked leaves.
+    pub fn redact_mut(&self, v: &mut Value) -> Vec<String> {
+        let mut masked = Vec::new();
+        let mut segments = Vec::new();
+        self.walk(v, &mut segments, &mut masked);
+        return masked;
+    }
+
+    /// Wrap the tree to display as toml text with matched leaves masked.
+    pub fn display<'a>(&'a self, v: &'a Value) -> Redacted<'a> {
+        Redacted { value: v, redactor: self }
+    }
+
+    fn walk(&self, v: &mut Value, segments: &mut Vec<String>, masked: &mut Vec<String>) {
+        // split the keys as the pattern, so quoted key `"db.password"` match `**.password`
+        let path: Vec<&str> = segments.iter()
+            .flat_map(|s| s.split(['/', '.']))
+            .filter(|s| !s.is_empty())
+            .collect();
+        if !segments.is_empty() && self.patterns.iter().any(|p| match_path(p, &path)) {
+            self.mask_all(v, &segments.join("."), masked);
+            return;
+        }
+        match v {
+            Value::Table(table) => {
+                for (k, sub) in table.iter_mut() {
+                    segments.push(k.clone());
+                    self.walk(sub, segments, masked);
+                    segments.pop();
+                }
+            },
+            Value::Array(array) => {
+                for (i, sub) in array.iter_mut().enumerate() {
+                    segments.push(i.to_string());
+                    self.walk(sub, segments, masked);
+                    segments.pop();
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn mask_all(&self, v: &mut Value, path: &str, masked: &mut Vec<String>) {
+        match v {
+            Value::Table(table) => {
+                for (k, sub) in table.iter_mut() {
+                    self.mask_all(sub, &join_key(path, k), masked);
+                }
+            },
+            Value::Array(array) => {
+                for (i, sub) in array.iter_mut().enumerate() {
+                    self.mask_all(sub, &join_key(path, &i.to_string()), masked);
+                }
+            },
+            _ => {
+                *v = Value::String(self.mask.clone());
+                masked.push(path.to_string());
+            },
+        }
+    }
+}
+
+/// Match path segments against pattern segments, `**` match zero or more segments.
+fn match_path<S: AsRef<str>>(pattern: &[S], path: &[&str]) -> bool {
+    let Some(first) = pattern.first() else {
+        return path.is_empty();
+    };
+    if first.as_ref() == "**" {
+        return (0..=path.len()).any(|i| match_path(&pattern[1..], &path[i..]));
+    }
+    match path.first() {
+        Some(seg) => match_glob(first.as_ref(), seg) && match_path(&pattern[1..], &path[1..]),
+        None => false,
+    }
+}
+
+/// Match one segment with `*` wildcard in it.
+fn match_glob(glob: &str, text: &str) -> bool {
+    let Some((head, rest)) = glob.split_once('*') else {
+        return glob == text;
+    };
+    let Some(text) = text.strip_prefix(head) else {
+        return false;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    return (0..=text.len()).filter(|&i| text.is_char_boundary(i)).any(|i| match_glob(rest, &text[i..]));
+}
+
+/// Display wrapper that print toml text with secret leaves masked.
+pub struct Redacted<'a> {
+    value: &'a Value,
+    redactor: &'a Redactor,
+}
+
+impl<'a> fmt::Display for Redacted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let safe = self.redactor.redact(self.value);
+        write!(f, "{}", format_toml(&safe, &FormatOptions::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_toml;
+
+#[test]
+fn pattern_test() {
+    let redactor = Redactor::new(["**.password", "db.*.token", "host/ip", "*_key"]);
+    assert_eq!(redactor.is_match("password"), true);
+    assert_eq!(redactor.is_match("a.b.c.password"), true);
+    assert_eq!(redactor.is_match("a.password.c"), false);
+    assert_eq!(redactor.is_match("db.main.token"), true);
+    assert_eq!(redactor.is_match("db.token"), false);
+    assert_eq!(redactor.is_match("db.a.b.token"), false);
+    assert_eq!(redactor.is_match("host.ip"), true);
+    assert_eq!(redactor.is_match("api_key"), true);
+    assert_eq!(redactor.is_match("api_keys"), false);
+
+    assert_eq!(match_glob("a*b*c", "axxbyyc"), true);
+    assert_eq!(match_glob("a*b*c", "axxbyy"), false);
+    assert_eq!(match_glob("*", ""), true);
+}
+
+#[test]
+fn redact_test() {
+    let v = load_test_toml();
+    let redactor = Redactor::new(["host.ip", "service.*.desc", "host/protocol"]).mask("<hidden>");
+
+    let safe = redactor.redact(&v);
+    assert_eq!(safe.path() / "ip" | "", "127.0.0.1");
+    assert_eq!(safe.path() / "host" / "ip" | "", "<hidden>");
+    assert_eq!(safe.path() / "host" / "port" | 0, 8080);
+    assert_eq!(safe.path() / "host" / "protocol" / 2 | "", "<hidden>");
+    assert_eq!(safe.path() / "service" / 1 / "desc" | "", "<hidden>");
+    assert_eq!(safe.path() / "service" / 1 / "name" | "", "serv_2");
+    assert_eq!(v.path() / "host" / "ip" | "", "127.0.1.1");
+
+    let mut v = v;
+    let masked = Redactor::new(["misc"]).redact_mut(&mut v);
+    assert_eq!(masked, vec!["misc.bool", "misc.float", "misc.int"]);
+    assert_eq!(v.path() / "misc" / "int" | "", "***");
+}
+
+#[test]
+fn quoted_key_test() {
+    let v: Value = "\"db.password\" = \"x\"\n[\"a/b\"]\n\"c.token\" = \"y\"\nname = \"z\"\n".parse().unwrap();
+    let mut safe = v.clone();
+    let masked = Redactor::new(["**.password", "a/b/c/token"]).redact_mut(&mut safe);
+    assert_eq!(masked, vec!["a/b.c.token", "db.password"]);
+    assert_eq!(safe.get("db.password"), Some(&Value::from("***")));
+    assert_eq!(safe["a/b"].get("c.token"), Some(&Value::from("***")));
+    assert_eq!(safe["a/b"].get("name"), Some(&Value::from("z")));
+}
+
+#[test]
+fn display_test() {
+    let v = load_test_toml();
+    let redactor = Redactor::new(["**.ip"]);
+    let text = format!("{}", redactor.display(&v));
+    assert_eq!(text.contains("127.0"), false);
+    assert_eq!(text.starts_with("ip = \"***\"\n\n[host]\nip = \"***\"\nport = 8080\n"), true);
+}
@@ -0,0 +1,142 @@
+//! Typed path segments, which distinguish table key and array index,
+//! and the `toml_path!` macro to build them at compile time.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, toml_path};
+//! let mut v: toml::Value = "[host]\nport = 8080\nprotocol = [\"tcp\", \"udp\"]".parse().unwrap();
+//!
+//! let proto = v.path() / toml_path!(host.protocol[1]) | "";
+//! assert_eq!(proto, "udp");
+//!
+//! let _ = v.path_mut() / toml_path!(host.port) << 9090;
+//! let port = v.path() / toml_path!(host."port") | 0;
+//! assert_eq!(port, 9090);
+//! ```
+//!
+//! Malformed path is rejected by compiler:
+//! ```rust,compile_fail
+//! let path = tomloper::toml_path!(host..port);
+//! ```
+//! ```rust,compile_fail
+//! let path = tomloper::toml_path!(host.protocol["1"]);
+//! ```
+//! ```rust,compile_fail
+//! let path = tomloper::toml_path!(host.protocol[-1]);
+//! ```
+
+use std::ops::Div;
+
+use toml::Value;
+
+use crate::{TomlPtr, TomlPtrMut, step, step_mut};
+
+/// One segment of path, as key for table or index for array.
+/// `Auto` is decided by the node as string path does,
+/// key for table and index for array, which may be negative.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+    Auto(&'a str),
+}
+
+/// Path made of static segment list, usually built by `toml_path!` macro.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StaticPath {
+    segments: &'static [Segment<'static>],
+}
+
+impl StaticPath {
+    /// Construct from segment list, can be used in const context.
+    pub const fn new(segments: &'static [Segment<'static>]) -> Self {
+        Self { segments }
+    }
+
+    /// Get the segment list.
+    pub fn segments(&self) -> &'static [Segment<'static>] {
+        self.segments
+    }
+}
+
+/// Build `StaticPath` from path literal at compile time,
+/// table key is written as identifier or string literal, and array index in bracket,
+/// eg. `toml_path!(host.protocol[2])` or `toml_path!(service[0]."desc")`.
+#[macro_export]
+macro_rules! toml_path {
+    (@first [$($seg:expr,)*] $key:ident $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Key(stringify!($key)),] $($rest)*)
+    };
+    (@first [$($seg:expr,)*] $key:literal $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Key($key),] $($rest)*)
+    };
+    (@first [$($seg:expr,)*] [$index:literal] $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Index($index),] $($rest)*)
+    };
+    (@rest [$($seg:expr,)*] . $key:ident $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Key(stringify!($key)),] $($rest)*)
+    };
+    (@rest [$($seg:expr,)*] . $key:literal $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Key($key),] $($rest)*)
+    };
+    (@rest [$($seg:expr,)*] [$index:literal] $($rest:tt)*) => {
+        $crate::toml_path!(@rest [$($seg,)* $crate::Segment::Index($index),] $($rest)*)
+    };
+    (@rest [$($seg:expr,)*]) => {
+        &[$($seg,)*]
+    };
+    ($($path:tt)+) => {{
+        const SEGMENTS: &[$crate::Segment<'static>] = $crate::toml_path!(@first [] $($path)+);
+        $crate::StaticPath::new(SEGMENTS)
+    }};
+}
+
+/// Resolve typed segments readonly, no guess of index or key as string path does.
+pub(crate) fn apply_segments<'tr>(v: &'tr Value, segments: &[Segment]) -> Option<&'tr Value> {
+    let mut target = v;
+    for seg in segments {
+        target = match (seg, target) {
+            (Segment::Key(k), Value::Table(table)) => table.get(*k)?,
+            (Segment::Index(i), Value::Array(array)) => array.get(*i)?,
+            (Segment::Auto(k), v) => step(v, k)?,
+            _ => return None,
+        };
+    }
+    return Some(target);
+}
+
+/// Resolve typed segments for mutable `toml::Value`.
+pub(crate) fn apply_segments_mut<'tr>(v: &'tr mut Value, segments: &[Segment]) -> Option<&'tr mut Value> {
+    let mut target = v;
+    for seg in segments {
+        target = match (seg, target) {
+            (Segment::Key(k), Value::Table(table)) => table.get_mut(*k)?,
+            (Segment::Index(i), Value::Array(array)) => array.get_mut(*i)?,
+            (Segment::Auto(k), v) => step_mut(v, k)?,
+            _ => return None,
+        };
+    }
+    return Some(target);
+}
+
+/// Path operator `/` with static path, eg. `tomlptr / toml_path!(host.port)`.
+impl<'tr> Div<StaticPath> for TomlPtr<'tr>
+{
+    type Output = Self;
+    fn div(self, rhs: StaticPath) -> Self::Output {
+        let valop = self.valop.and_then(|v| apply_segments(v, rhs.segments));
+        TomlPtr { valop }
+    }
+}
+
+/// Path operator `/` with static path for mutable pointer.
+impl<'tr> Div<StaticPath> for TomlPtrMut<'tr>
+{
+    type Output = Self;
+    fn div(self, rhs: StaticPath) -> Self::Output {
+        let valop = self.valop.and_then(|v| apply_segments_mut(v, rhs.segments));
+        TomlPtrMut { valop }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,58 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_toml;
+
+#[test]
+fn toml_path_macro_test() {
+    let path = toml_path!(host.protocol[2]);
+    assert_eq!(path.segments(), &[Segment::Key("host"), Segment::Key("protocol"), Segment::Index(2)]);
+
+    let path = toml_path!(service[0]."desc");
+    assert_eq!(path.segments(), &[Segment::Key("service"), Segment::Index(0), Segment::Key("desc")]);
+
+    let path = toml_path!("no-key");
+    assert_eq!(path.segments(), &[Segment::Key("no-key")]);
+
+    let path = toml_path!([1]);
+    assert_eq!(path.segments(), &[Segment::Index(1)]);
+
+    const PORT: StaticPath = toml_path!(host.port);
+    assert_eq!(PORT.segments().len(), 2);
+}
+
+#[test]
+fn auto_segment_test() {
+    let v: Value = "list = [1, 2]\n[code]\n404 = \"not found\"".parse().unwrap();
+    const CODE: StaticPath = StaticPath::new(&[Segment::Key("code"), Segment::Auto("404")]);
+    const LAST: StaticPath = StaticPath::new(&[Segment::Key("list"), Segment::Auto("-1")]);
+    assert_eq!(v.path() / CODE | "", "not found");
+    assert_eq!(v.path() / LAST | 0, 2);
+    assert_eq!(v.path() / StaticPath::new(&[Segment::Key("list"), Segment::Auto("x")]) | 0, 0);
+}
+
+#[test]
+fn static_path_test() {
+    let mut v = load_test_toml();
+
+    let proto = v.path() / toml_path!(host.protocol[2]) | "";
+    assert_eq!(proto, "mmp");
+    let desc = v.path() / toml_path!(service[1]) / "desc" | "";
+    assert_eq!(desc, "another server");
+    let node = v.path() / toml_path!(host.protocol[3]);
+    assert_eq!(node.is_none(), true);
+
+    // index is not used as table key, nor key as index
+    let node = v.path() / toml_path!(host[0]);
+    assert_eq!(node.is_none(), true);
+    let node = v.path() / toml_path!(host.protocol."0");
+    assert_eq!(node.is_none(), true);
+
+    let node = v.path_mut() / toml_path!(host.protocol[0]) << "quic";
+    assert_eq!(node | "", "quic");
+    let node = v.path_mut() / toml_path!(misc) / toml_path!(int) << 4321;
+    assert_eq!(node | 0, 4321);
+    let node = v.path_mut() / toml_path!(misc.none) << 1;
+    assert_eq!(node.is_none(), true);
+}
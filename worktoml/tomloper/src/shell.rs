@@ -0,0 +1,379 @@
+//! Command interpreter to explore and patch toml document interactively,
+//! the line editor front end is the `tomlsh` binary.
+//!
+//! Path argument is relative to current node, or absolute if start with `/`,
+//! and `..` refer to parent node. Commands:
+//!
+//! * `PATH` or `get PATH`: print the node.
+//! * `cd [PATH]`: change current node, to root if no path.
+//! * `ls [PATH]`: list children of table or array.
+//! * `pwd`: print current path.
+//! * `set PATH VALUE`: set value in toml syntax, or plain string, create key if missing.
+//! * `del PATH`: remove key from table or item from array.
+//! * `save [FILE]`: write the document back to file, with comments and key order kept.
+//!
+//! ```rust
+//! use tomloper::Shell;
+//! let mut sh = Shell::new("[host]\nport = 8080\nprotocol = [\"tcp\", \"udp\"]".parse().unwrap());
+//! assert_eq!(sh.exec("host/protocol/1").unwrap(), "\"udp\"");
+//! sh.exec("cd host").unwrap();
+//! sh.exec("set port 9090").unwrap();
+//! assert_eq!(sh.exec("port").unwrap(), "9090");
+//! assert_eq!(sh.complete("pro"), vec!["protocol/"]);
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+
+use toml::Value;
+use toml_edit::{DocumentMut, Item};
+
+use crate::{PathOperator, ValueTree, format_toml, FormatOptions, resolve_mut};
+use crate::format::format_inline;
+use crate::layered::parse_scalar;
+
+/// Error of shell command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellError {
+    /// The path does not exist or is not of required type.
+    NoPath(String),
+    /// Bad arguments, with the usage of command.
+    Usage(&'static str),
+    /// Unknown command.
+    Unknown(String),
+    /// Fail to save file.
+    Io(String),
+    /// The edit cannot be applied to the file layout without losing comments, nothing changed.
+    NotInPlace(String),
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShellError::NoPath(p) => write!(f, "no such path: {}", p),
+            ShellError::Usage(usage) => write!(f, "usage: {}", usage),
+            ShellError::Unknown(cmd) => write!(f, "unknown command: {}", cmd),
+            ShellError::Io(msg) => write!(f, "io error: {}", msg),
+            ShellError::NotInPlace(p) => write!(f, "cannot edit in place: {}", p),
+        }
+    }
+}
+
+impl std::error::Error for ShellError {}
+
+/// Toml document with current path, and the file to save.
+/// The edits are also applied to the `toml_edit` document, which is what to save.
+pub struct Shell {
+    doc: Value,
+    edit: DocumentMut,
+    cwd: Vec<String>,
+    file: Option<PathBuf>,
+    dirty: bool,
+}
+
+impl Shell {
+    /// Shell on a document not bound to file.
+    pub fn new(doc: Value) -> Self {
+        let edit = edit_document(&doc);
+        Self { doc, edit, cwd: Vec::new(), file: None, dirty: false }
+    }
+
+    /// Load document from toml file, which is also the default file to save.
+    pub fn open<P: Into<PathBuf>>(file: P) -> Result<Self, String> {
+        let file = file.into();
+        let text = std::fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        let doc = text.parse().map_err(|e| format!("{}: {}", file.display(), e))?;
+        let edit = text.parse().map_err(|e| format!("{}: {}", file.display(), e))?;
+        Ok(Self { doc, edit, cwd: Vec::new(), file: Some(file), dirty: false })
+    }
+
+    /// The whole document.
+    pub fn doc(&self) -> &Value {
+        &self.doc
+    }
+
+    /// Current path as `/a/b`.
+    pub fn pwd(&self) -> String {
+        format!("/{}", self.cwd.join("/"))
+    }
+
+    /// If the document is modified after last save.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Execute a command line, return the text to print.
+    pub fn exec(&mut self, line: &str) -> Result<String, ShellError> {
+        let line = line.trim();
+        let (cmd, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args.trim();
+        match cmd {
+            "" => Ok(String::new()),
+            "get" => self.get(args),
+            "cd" => self.cd(args),
+            "ls" => self.ls(args),
+            "pwd" => Ok(self.pwd()),
+            "set" => {
+                let Some((p, val)) = args.split_once(char::is_whitespace) else {
+                    return Err(ShellError::Usage("set PATH VALUE"));
+                };
+                self.set(p, val.trim())
+            },
+            "del" if !args.is_empty() => self.del(args),
+            "del" => Err(ShellError::Usage("del PATH")),
+            "save" => self.save(args),
+            _ if args.is_empty() => self.get(cmd),
+            _ => Err(ShellError::Unknown(cmd.to_string())),
+        }
+    }
+
+    /// Complete the last path word in command line, return candidates for the word.
+    /// Table or array candidate end with `/` to continue completion.
+    pub fn complete(&self, word: &str) -> Vec<String> {
+        let (dir, partial) = match word.rfind('/') {
+            Some(i) => (&word[..=i], &word[i+1..]),
+            None => ("", word),
+        };
+        let segments = self.resolve(dir);
+        let Some(node) = *self.doc.pathto(&segments.join("/")).unpath() else {
+            return Vec::new();
+        };
+        let children: Vec<(String, &Value)> = match node {
+            Value::Table(table) => table.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            Value::Array(array) => array.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+            _ => Vec::new(),
+        };
+        children.into_iter()
+            .filter(|(k, _)| k.starts_with(partial))
+            .map(|(k, v)| {
+                let tail = if v.is_table() || v.is_array() { "/" } else { "" };
+                format!("{}{}{}", dir, k, tail)
+            })
+            .collect()
+    }
+
+    /// Resolve path relative to current node as segments from root.
+    fn resolve(&self, p: &str) -> Vec<String> {
+        let mut segments = if p.starts_with('/') { Vec::new() } else { self.cwd.clone() };
+        for seg in p.split('/') {
+            if seg == ".." {
+                segments.pop();
+                continue;
+            }
+            segments.extend(seg.split('.').filter(|s| !s.is_empty()).map(String::from));
+        }
+        return segments;
+    }
+
+    /// Resolve path and check it exist.
+    fn node(&self, p: &str) -> Result<(Vec<String>, &Value), ShellError> {
+        let segments = self.resolve(p);
+        match *self.doc.pathto(&segments.join("/")).unpath() {
+            Some(v) => Ok((segments, v)),
+            None => Err(ShellError::NoPath(p.to_string())),
+        }
+    }
+
+    fn get(&self, p: &str) -> Result<String, ShellError> {
+        let (_, v) = self.node(p)?;
+        let text = match v {
+            Value::Table(_) => format_toml(v, &FormatOptions::default()).trim_end().to_string(),
+            _ => format_inline(v),
+        };
+        Ok(text)
+    }
+
+    fn cd(&mut self, p: &str) -> Result<String, ShellError> {
+        let (segments, v) = self.node(if p.is_empty() { "/" } else { p })?;
+        if !v.is_table() && !v.is_array() {
+            return Err(ShellError::NoPath(p.to_string()));
+        }
+        self.cwd = segments;
+        Ok(String::new())
+    }
+
+    fn ls(&self, p: &str) -> Result<String, ShellError> {
+        let (_, v) = self.node(p)?;
+        let entry = |k: &str, v: &Value| match v {
+            Value::Table(_) => format!("{}/", k),
+            Value::Array(array) if !array.is_empty() && array.iter().all(|v| v.is_table()) => {
+                format!("{}/ [{}]", k, array.len())
+            },
+            _ => format!("{} = {}", k, format_inline(v)),
+        };
+        let lines: Vec<String> = match v {
+            Value::Table(table) => table.iter().map(|(k, v)| entry(k, v)).collect(),
+            Value::Array(array) => array.iter().enumerate().map(|(i, v)| entry(&i.to_string(), v)).collect(),
+            _ => vec![format_inline(v)],
+        };
+        Ok(lines.join("\n"))
+    }
+
+    fn set(&mut self, p: &str, val: &str) -> Result<String, ShellError> {
+        let mut segments = self.resolve(p);
+        let Some(key) = segments.pop() else {
+            return Err(ShellError::Usage("set PATH VALUE"));
+        };
+        let val = parse_scalar(val);
+        let inline = format_inline(&val);
+        // edit a copy, so that nothing is changed if the file cannot be edited as well
+        let mut doc = self.doc.clone();
+        let parent = doc.pathto_mut(&segments.join("/"));
+        let node = match (parent.as_deref().map(|v| v.is_array()), key.parse::<usize>()) {
+            (Some(true), Ok(index)) => {
+                let len = parent.as_deref().and_then(|v| v.as_array()).map_or(0, |a| a.len());
+                if index == len {
+                    parent << (val,)
+                } else {
+                    let mut node = parent / index;
+                    node <<= val;
+                    node
+                }
+            },
+            _ => parent << (key.as_str(), val),
+        };
+        if !node {
+            return Err(ShellError::NoPath(p.to_string()));
+        }
+        if edit_set(&mut self.edit, &segments, &key, &inline).is_none() {
+            return Err(ShellError::NotInPlace(p.to_string()));
+        }
+        self.doc = doc;
+        self.dirty = true;
+        Ok(String::new())
+    }
+
+    fn del(&mut self, p: &str) -> Result<String, ShellError> {
+        let mut segments = self.resolve(p);
+        let Some(key) = segments.pop() else {
+            return Err(ShellError::NoPath(p.to_string()));
+        };
+        let mut doc = self.doc.clone();
+        let removed = match doc.pathto_mut(&segments.join("/")).as_deref_mut() {
+            Some(Value::Table(table)) => table.remove(&key).is_some(),
+            Some(Value::Array(array)) => match key.parse::<usize>() {
+                Ok(index) if index < array.len() => {
+                    array.remove(index);
+                    true
+                },
+                _ => false,
+            },
+            _ => false,
+        };
+        if !removed {
+            return Err(ShellError::NoPath(p.to_string()));
+        }
+        if edit_del(&mut self.edit, &segments, &key).is_none() {
+            return Err(ShellError::NotInPlace(p.to_string()));
+        }
+        self.doc = doc;
+        if self.doc.pathto(&self.cwd.join("/")).is_none() {
+            self.cwd = segments;
+        }
+        self.dirty = true;
+        Ok(String::new())
+    }
+
+    fn save(&mut self, file: &str) -> Result<String, ShellError> {
+        if !file.is_empty() {
+            self.file = Some(PathBuf::from(file));
+        }
+        let Some(file) = &self.file else {
+            return Err(ShellError::Usage("save FILE"));
+        };
+        std::fs::write(file, self.edit.to_string()).map_err(|e| ShellError::Io(format!("{}: {}", file.display(), e)))?;
+        self.dirty = false;
+        Ok(format!("saved to {}", file.display()))
+    }
+}
+
+/// Build the `toml_edit` document from value tree, when no source text to keep.
+fn edit_document(doc: &Value) -> DocumentMut {
+    format_toml(doc, &FormatOptions::default()).parse().unwrap_or_default()
+}
+
+/// Replace the value but keep its decor, as the comment after it.
+fn replace_value(old: &mut toml_edit::Value, new: toml_edit::Value) {
+    let decor = old.decor().clone();
+    *old = new;
+    *old.decor_mut() = decor;
+}
+
+/// Replace the table of `[[array]]` item but keep its decor, as the comments before header.
+fn replace_table(old: &mut toml_edit::Table, mut new: toml_edit::Table) {
+    *new.decor_mut() = old.decor().clone();
+    if let Some(position) = old.position() {
+        new.set_position(position);
+    }
+    *old = new;
+}
+
+/// Apply `set` of inline value text to the `toml_edit` document,
+/// `None` if it cannot be done in place, and then the document is not changed.
+fn edit_set(edit: &mut DocumentMut, segments: &[String], key: &str, inline: &str) -> Option<()> {
+    let new: toml_edit::Value = inline.parse().ok()?;
+    let parent = resolve_mut(edit.as_item_mut(), &segments.join("/"))?;
+    if let Item::ArrayOfTables(array) = parent {
+        // only table can be item of `[[array]]`
+        let index = key.parse::<usize>().ok()?;
+        let toml_edit::Value::InlineTable(table) = new else {
+            return None;
+        };
+        let table = table.into_table();
+        if index == array.len() {
+            array.push(table);
+        } else {
+            replace_table(array.get_mut(index)?, table);
+        }
+        return Some(());
+    }
+    if let Some(len) = parent.array_len() {
+        let index = key.parse::<usize>().ok()?;
+        let array = parent.as_array_mut()?;
+        if index == len {
+            array.push(new);
+        } else {
+            replace_value(array.get_mut(index)?, new);
+        }
+        return Some(());
+    }
+
+    let table = parent.as_table_like_mut()?;
+    match table.get_mut(key) {
+        Some(Item::Value(old)) => replace_value(old, new),
+        Some(slot) => *slot = Item::Value(new),
+        None => {
+            table.insert(key, Item::Value(new));
+        },
+    }
+    return Some(());
+}
+
+/// Apply `del` to the `toml_edit` document,
+/// `None` if it cannot be done in place, and then the document is not changed.
+fn edit_del(edit: &mut DocumentMut, segments: &[String], key: &str) -> Option<()> {
+    let parent = resolve_mut(edit.as_item_mut(), &segments.join("/"))?;
+    if let Some(len) = parent.array_len() {
+        let index = key.parse::<usize>().ok().filter(|i| *i < len)?;
+        match parent {
+            Item::ArrayOfTables(array) => array.remove(index),
+            _ => {
+                let array = parent.as_array_mut()?;
+                let removed = array.remove(index);
+                // the new first item take the prefix, if it has no comment
+                if let Some(first) = array.get_mut(index).filter(|_| index == 0) {
+                    let blank = first.decor().prefix().and_then(|p| p.as_str()).is_none_or(|p| p.trim().is_empty());
+                    if blank {
+                        first.decor_mut().set_prefix(removed.decor().prefix().cloned().unwrap_or_default());
+                    }
+                }
+            },
+        }
+        return Some(());
+    }
+    parent.as_table_like_mut()?.remove(key)?;
+    return Some(());
+}
+
+#[cfg(test)]
+mod tests;
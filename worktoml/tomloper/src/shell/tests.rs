@@ -0,0 +1,126 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::{load_test_text, load_test_toml};
+
+#[test]
+fn read_test() {
+    let mut sh = Shell::new(load_test_toml());
+    assert_eq!(sh.exec("host/protocol/1").unwrap(), "\"udp\"");
+    assert_eq!(sh.exec("get service.0.name").unwrap(), "\"serv_1\"");
+    assert_eq!(sh.exec("misc").unwrap(), "bool = true\nfloat = 3.14\nint = 1234");
+    assert_eq!(sh.exec("ls").unwrap(), "host/\nip = \"127.0.0.1\"\nmisc/\nservice/ [2]");
+
+    assert_eq!(sh.exec("cd service/1").unwrap(), "");
+    assert_eq!(sh.pwd(), "/service/1");
+    assert_eq!(sh.exec("ls").unwrap(), "desc = \"another server\"\nname = \"serv_2\"");
+    assert_eq!(sh.exec("../0/name").unwrap(), "\"serv_1\"");
+    assert_eq!(sh.exec("/ip").unwrap(), "\"127.0.0.1\"");
+    assert_eq!(sh.exec("cd ..").unwrap(), "");
+    assert_eq!(sh.pwd(), "/service");
+    assert_eq!(sh.exec("cd").unwrap(), "");
+    assert_eq!(sh.pwd(), "/");
+
+    assert_eq!(sh.exec("cd ip"), Err(ShellError::NoPath("ip".to_string())));
+    assert_eq!(sh.exec("nothing"), Err(ShellError::NoPath("nothing".to_string())));
+    assert_eq!(sh.exec("nothing here"), Err(ShellError::Unknown("nothing".to_string())));
+    assert_eq!(sh.is_dirty(), false);
+}
+
+#[test]
+fn write_test() {
+    let mut sh = Shell::new(load_test_toml());
+    sh.exec("cd host").unwrap();
+    sh.exec("set port 9090").unwrap();
+    sh.exec("set debug true").unwrap();
+    sh.exec("set protocol/3 quic").unwrap();
+    sh.exec("set protocol/0 \"sctp\"").unwrap();
+    sh.exec("set /misc/list [1, 2]").unwrap();
+    assert_eq!(sh.is_dirty(), true);
+    assert_eq!(sh.exec("port").unwrap(), "9090");
+    assert_eq!(sh.exec("debug").unwrap(), "true");
+    assert_eq!(sh.exec("protocol").unwrap(), "[\"sctp\", \"udp\", \"mmp\", \"quic\"]");
+    assert_eq!(sh.exec("/misc/list").unwrap(), "[1, 2]");
+    assert_eq!(sh.exec("set protocol/9 x"), Err(ShellError::NoPath("protocol/9".to_string())));
+    assert_eq!(sh.exec("set none/key x"), Err(ShellError::NoPath("none/key".to_string())));
+    assert_eq!(sh.exec("set port"), Err(ShellError::Usage("set PATH VALUE")));
+
+    sh.exec("del protocol/1").unwrap();
+    sh.exec("del /ip").unwrap();
+    assert_eq!(sh.exec("protocol").unwrap(), "[\"sctp\", \"mmp\", \"quic\"]");
+    assert_eq!(sh.exec("/ip").is_err(), true);
+    sh.exec("del /host").unwrap();
+    assert_eq!(sh.pwd(), "/");
+    assert_eq!(sh.exec("del host"), Err(ShellError::NoPath("host".to_string())));
+}
+
+#[test]
+fn complete_test() {
+    let mut sh = Shell::new(load_test_toml());
+    assert_eq!(sh.complete(""), vec!["host/", "ip", "misc/", "service/"]);
+    assert_eq!(sh.complete("host/p"), vec!["host/port", "host/protocol/"]);
+    assert_eq!(sh.complete("service/"), vec!["service/0/", "service/1/"]);
+    assert_eq!(sh.complete("none/"), Vec::<String>::new());
+    sh.exec("cd misc").unwrap();
+    assert_eq!(sh.complete("f"), vec!["float"]);
+    assert_eq!(sh.complete("../i"), vec!["../ip"]);
+}
+
+#[test]
+fn save_test() {
+    let file = std::env::temp_dir().join(format!("tomloper-shell-{}.toml", std::process::id()));
+    std::fs::write(&file, load_test_text()).unwrap();
+
+    let mut sh = Shell::open(&file).unwrap();
+    sh.exec("set host/port 9090").unwrap();
+    assert_eq!(sh.exec("save").unwrap(), format!("saved to {}", file.display()));
+    assert_eq!(sh.is_dirty(), false);
+
+    let sh = Shell::open(&file).unwrap();
+    assert_eq!(sh.doc().get("host").and_then(|h| h.get("port")), Some(&Value::from(9090)));
+    std::fs::remove_file(&file).unwrap();
+
+    let mut sh = Shell::new(load_test_toml());
+    assert_eq!(sh.exec("save"), Err(ShellError::Usage("save FILE")));
+}
+
+#[test]
+fn save_comment_test() {
+    let file = std::env::temp_dir().join(format!("tomloper-shell-comment-{}.toml", std::process::id()));
+    let text = "# top\nname = \"x\"\n\n[host]\n# the port\nport = 8080 # why\nip = \"::1\"\nprotocol = [\"tcp\", \"udp\"] # list\n\n[[service]]\nname = \"a\"\n\n[[service]]\nname = \"b\"\n";
+    std::fs::write(&file, text).unwrap();
+
+    let mut sh = Shell::open(&file).unwrap();
+    sh.exec("set host/port 9090").unwrap();
+    sh.exec("set host/tls true").unwrap();
+    sh.exec("set host/protocol/2 quic").unwrap();
+    sh.exec("del host/protocol/0").unwrap();
+    sh.exec("del service/0").unwrap();
+    sh.exec("save").unwrap();
+
+    let saved = std::fs::read_to_string(&file).unwrap();
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(saved, "# top\nname = \"x\"\n\n[host]\n# the port\nport = 9090 # why\nip = \"::1\"\nprotocol = [\"udp\", \"quic\"] # list\ntls = true\n\n[[service]]\nname = \"b\"\n");
+    assert_eq!(saved.parse::<Value>().unwrap(), *sh.doc());
+}
+
+#[test]
+fn save_array_of_tables_test() {
+    let file = std::env::temp_dir().join(format!("tomloper-shell-aot-{}.toml", std::process::id()));
+    let text = "ip = \"::1\" # why\n\n# keep me\n[[service]]\nname = \"a\"\nport = 80\n\n[[service]]\nname = \"b\"\n";
+    std::fs::write(&file, text).unwrap();
+
+    let mut sh = Shell::open(&file).unwrap();
+    sh.exec("set service/0 {name = \"z\"}").unwrap();
+    sh.exec("set service/2 {name = \"c\"}").unwrap();
+    sh.exec("del service/1").unwrap();
+    // scalar cannot be item of `[[service]]`, and nothing is changed
+    assert_eq!(sh.exec("set service/0 5"), Err(ShellError::NotInPlace("service/0".to_string())));
+    assert_eq!(sh.exec("service/0/name").unwrap(), "\"z\"");
+    sh.exec("save").unwrap();
+
+    let saved = std::fs::read_to_string(&file).unwrap();
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(saved, "ip = \"::1\" # why\n\n# keep me\n[[service]]\nname = \"z\"\n\n[[service]]\nname = \"c\"\n");
+    assert_eq!(saved.parse::<Value>().unwrap(), *sh.doc());
+}
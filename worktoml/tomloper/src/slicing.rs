@@ -0,0 +1,169 @@
+//! Python-like slice of array in path, as `protocol[1:]` or `service[-2:]/name`.
+//!
+//! Slice select several nodes, so it is not supported by `pathto()` which point to
+//! single node. `select()` resolve path with slices to node list instead,
+//! and `TomlPtrMut::splice()` replace the range of array with new items.
+//! Negative index count from the end, and out of range bound is clamped.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, SliceOperator};
+//! let mut v: toml::Value = "[host]\nprotocol = [\"tcp\", \"udp\", \"mmp\"]\n[[service]]\nname = \"s1\"\n[[service]]\nname = \"s2\"".parse().unwrap();
+//!
+//! let tail: Vec<&str> = v.select("host/protocol[1:]").iter().filter_map(|p| p.as_str()).collect();
+//! assert_eq!(tail, vec!["udp", "mmp"]);
+//! assert_eq!(v.select("service[:]/name").len(), 2);
+//! assert_eq!(v.pathto("service/-1/name") | "", "s2");
+//!
+//! let node = v.pathto_mut("host/protocol").splice("1:", ["quic"]);
+//! assert_eq!(node.is_none(), false);
+//! assert_eq!(v.pathto("host/protocol/-1") | "", "quic");
+//! assert_eq!(v.select("host/protocol[:]").len(), 2);
+//! ```
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use toml::Value;
+
+use crate::{PathBuilder, TomlPtr, TomlPtrMut, step, step_mut};
+
+/// Slice `start:end` of array, either bound can be omitted or negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Slice {
+    pub start: Option<isize>,
+    pub end: Option<isize>,
+}
+
+impl Slice {
+    /// Index range in array of `len`, clamped in `0..len`, empty if start is after end.
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let clamp = |i: isize| -> usize {
+            if i < 0 {
+                return len.saturating_sub(i.unsigned_abs());
+            }
+            (i as usize).min(len)
+        };
+        let start = self.start.map_or(0, clamp);
+        let end = self.end.map_or(len, clamp).max(start);
+        start..end
+    }
+}
+
+impl FromStr for Slice {
+    type Err = ();
+    /// Parse `start:end`, optionally in bracket as `[start:end]`.
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let s = s.trim();
+        let s = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')).unwrap_or(s);
+        let (start, end) = s.split_once(':').ok_or(())?;
+        let bound = |b: &str| -> Result<Option<isize>, ()> {
+            let b = b.trim();
+            if b.is_empty() {
+                return Ok(None);
+            }
+            b.parse().map(Some).map_err(|_| ())
+        };
+        Ok(Slice { start: bound(start)?, end: bound(end)? })
+    }
+}
+
+/// Step of path with slices.
+enum Step {
+    Key(String),
+    Slice(Slice),
+}
+
+/// Split path on slash(/) or dot(.), and `key[a:b]` segment into key and slice.
+fn parse_steps(p: &str) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for seg in p.build_path().paths.into_iter().filter(|s| !s.is_empty()) {
+        let slice = seg.find('[')
+            .filter(|_| seg.ends_with(']'))
+            .and_then(|i| Some((i, seg[i..].parse::<Slice>().ok()?)));
+        match slice {
+            Some((i, slice)) => {
+                if i > 0 {
+                    steps.push(Step::Key(seg[..i].to_string()));
+                }
+                steps.push(Step::Slice(slice));
+            },
+            None => steps.push(Step::Key(seg)),
+        }
+    }
+    return steps;
+}
+
+/// Resolve path with slices to multiple nodes.
+pub trait SliceOperator {
+    /// All nodes that the path select, in array order, empty if none.
+    fn select<'tr>(&'tr self, p: &str) -> Vec<&'tr Value>;
+
+    /// Mutable version of `select()`.
+    fn select_mut<'tr>(&'tr mut self, p: &str) -> Vec<&'tr mut Value>;
+}
+
+impl SliceOperator for Value {
+    fn select<'tr>(&'tr self, p: &str) -> Vec<&'tr Value> {
+        let mut nodes = vec![self];
+        for next in parse_steps(p) {
+            nodes = nodes.into_iter().flat_map(|v| match (&next, v) {
+                (Step::Key(key), v) => step(v, key).into_iter().collect(),
+                (Step::Slice(slice), Value::Array(array)) => {
+                    array[slice.range(array.len())].iter().collect()
+                },
+                _ => Vec::new(),
+            }).collect();
+        }
+        return nodes;
+    }
+
+    fn select_mut<'tr>(&'tr mut self, p: &str) -> Vec<&'tr mut Value> {
+        let mut nodes = vec![self];
+        for next in parse_steps(p) {
+            nodes = nodes.into_iter().flat_map(|v| match (&next, v) {
+                (Step::Key(key), v) => step_mut(v, key).into_iter().collect(),
+                (Step::Slice(slice), Value::Array(array)) => {
+                    let range = slice.range(array.len());
+                    array[range].iter_mut().collect()
+                },
+                _ => Vec::new(),
+            }).collect();
+        }
+        return nodes;
+    }
+}
+
+impl<'tr> TomlPtr<'tr> {
+    /// Items of array in slice such as `"1:"` or `"-2:"`,
+    /// empty if not array or bad slice.
+    pub fn slice(self, slice: &str) -> Vec<&'tr Value> {
+        match (self.valop, slice.parse::<Slice>()) {
+            (Some(Value::Array(array)), Ok(slice)) => array[slice.range(array.len())].iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Replace items of array in slice such as `"1:3"` with new items, as `Vec::splice()`.
+    /// Return pointer to the array, or invalid pointer if not array or bad slice.
+    pub fn splice<I>(mut self, slice: &str, items: I) -> Self
+    where I: IntoIterator, Value: From<I::Item>
+    {
+        let Ok(slice) = slice.parse::<Slice>() else {
+            return Self::none();
+        };
+        match self.valop.take() {
+            Some(v) if v.is_array() => {
+                let array = v.as_array_mut().unwrap();
+                let range = slice.range(array.len());
+                array.splice(range, items.into_iter().map(Value::from));
+                Self::path(v)
+            },
+            _ => Self::none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::{CompiledPath, PathOperator};
+use crate::tests::load_test_toml;
+
+#[test]
+fn negative_index_test() {
+    let mut v = load_test_toml();
+    assert_eq!(v.pathto("host/protocol/-1") | "", "mmp");
+    assert_eq!(v.pathto("host.protocol.-3") | "", "tcp");
+    assert_eq!(!v.pathto("host/protocol/-4"), true);
+    assert_eq!(v.pathto("service/-1/desc") | "", "another server");
+    assert_eq!(v.path() / "service" / "-2" / "name" | "", "serv_1");
+    assert_eq!(v.path() / "host" / "protocol" / "1" | "", "udp");
+    assert_eq!(v.path() / &CompiledPath::new("host/protocol/-2") | "", "udp");
+
+    let _ = v.pathto_mut("service/-1/name") << "last";
+    assert_eq!(v.pathto("service/1/name") | "", "last");
+    let _ = v.path_mut() / "host" / "protocol" / "-1" << "quic";
+    assert_eq!(v.pathto("host/protocol/2") | "", "quic");
+    let mut node = v.pathto_mut("host/protocol/-1");
+    node <<= 1;
+    assert_eq!(v.pathto("host/protocol/2") | 0, 1);
+}
+
+#[test]
+fn slice_parse_test() {
+    assert_eq!("1:".parse(), Ok(Slice { start: Some(1), end: None }));
+    assert_eq!("[:-1]".parse(), Ok(Slice { start: None, end: Some(-1) }));
+    assert_eq!(" 1 : 3 ".parse(), Ok(Slice { start: Some(1), end: Some(3) }));
+    assert_eq!(":".parse(), Ok(Slice::default()));
+    assert_eq!("1".parse::<Slice>(), Err(()));
+    assert_eq!("a:b".parse::<Slice>(), Err(()));
+
+    let slice = Slice { start: Some(-2), end: None };
+    assert_eq!(slice.range(5), 3..5);
+    assert_eq!(slice.range(1), 0..1);
+    assert_eq!(Slice { start: Some(4), end: Some(2) }.range(5), 4..4);
+    assert_eq!(Slice { start: Some(1), end: Some(10) }.range(3), 1..3);
+}
+
+#[test]
+fn select_test() {
+    let mut v = load_test_toml();
+    let nodes = v.select("host/protocol[1:]");
+    assert_eq!(nodes, vec![&Value::from("udp"), &Value::from("mmp")]);
+    assert_eq!(v.select("host.protocol[-1:]"), vec![&Value::from("mmp")]);
+    assert_eq!(v.select("host/protocol/[:1]"), vec![&Value::from("tcp")]);
+    assert_eq!(v.select("service[:]/name"), vec![&Value::from("serv_1"), &Value::from("serv_2")]);
+    assert_eq!(v.select("host/port"), vec![&Value::from(8080)]);
+    assert_eq!(v.select("host[1:]").is_empty(), true);
+    assert_eq!(v.select("none[:]").is_empty(), true);
+
+    assert_eq!(v.pathto("host/protocol").slice(":2").len(), 2);
+    assert_eq!(v.pathto("host/port").slice(":2").is_empty(), true);
+
+    for node in v.select_mut("service[:]/name") {
+        *node = Value::from("renamed");
+    }
+    assert_eq!(v.pathto("service/0/name") | "", "renamed");
+    assert_eq!(v.pathto("service/1/name") | "", "renamed");
+}
+
+#[test]
+fn splice_test() {
+    let mut v = load_test_toml();
+    let node = v.pathto_mut("host/protocol").splice("1:2", ["quic", "http"]);
+    assert_eq!(!node, false);
+    assert_eq!(v.select("host/protocol[:]").len(), 4);
+    assert_eq!(v.pathto("host/protocol/2") | "", "http");
+
+    let _ = v.pathto_mut("host/protocol").splice(":-1", Vec::<Value>::new());
+    assert_eq!(v.select("host/protocol[:]"), vec![&Value::from("mmp")]);
+
+    let _ = v.pathto_mut("host/protocol").splice("5:", [1, 2]);
+    assert_eq!(v.pathto("host/protocol/-1") | 0, 2);
+
+    assert_eq!(v.pathto_mut("host/port").splice(":", [1]).is_none(), true);
+    assert_eq!(v.pathto_mut("host/protocol").splice("x", [1]).is_none(), true);
+}
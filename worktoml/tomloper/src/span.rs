@@ -0,0 +1,202 @@
+//! Map path of toml node back to its source position, and render rustc-style diagnostic.
+//!
+//! ```rust
+//! use tomloper::SourceMap;
+//! let text = "[host]\nip = \"127.0.0.1\"\nport = 80800\n";
+//! let map = SourceMap::new("app.toml", text);
+//!
+//! let span = map.span_of("host/port").unwrap();
+//! assert_eq!((span.line, span.col), (3, 8));
+//! assert_eq!(&text[span.range], "80800");
+//!
+//! let diag = map.diagnostic("host.port", "port out of range").label("must below 65536");
+//! assert_eq!(diag.to_string(), "\
+//! error: port out of range
+//!  --> app.toml:3:8
+//!   |
+//! 3 | port = 80800
+//!   |        ^^^^^ must below 65536
+//! ");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{PathBuilder, join_key};
+
+/// Position of a node in source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Byte range in the text.
+    pub range: Range<usize>,
+    /// Line number, 1-based.
+    pub line: usize,
+    /// Column in chars, 1-based.
+    pub col: usize,
+}
+
+/// Spans of all nodes in a toml text, keyed by dotted path.
+/// Leaf value span cover the value, table span cover its `[header]`.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    name: String,
+    text: String,
+    spans: BTreeMap<String, Range<usize>>,
+}
+
+impl SourceMap {
+    /// Scan the text named as `name`, usually the file path.
+    /// Invalid toml text has no span.
+    pub fn new(name: &str, text: &str) -> Self {
+        let mut spans = BTreeMap::new();
+        if let Ok(doc) = toml_edit::ImDocument::parse(text) {
+            item_spans(doc.as_item(), "", &mut spans);
+        }
+        Self { name: name.to_string(), text: text.to_string(), spans }
+    }
+
+    /// Name of the source.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Source text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Span of the node at path, `None` if not exist or it is implicit table.
+    pub fn span_of(&self, p: &str) -> Option<Span> {
+        let key = p.build_path().to_key();
+        let range = self.spans.get(&key)?;
+        Some(self.span(range.clone()))
+    }
+
+    /// Span of the node at path, or of its nearest parent that has span.
+    pub fn nearest_span(&self, p: &str) -> Option<Span> {
+        let key = p.build_path().to_key();
+        let mut key = key.as_str();
+        loop {
+            if let Some(range) = self.spans.get(key) {
+                return Some(self.span(range.clone()));
+            }
+            key = &key[..key.rfind('.')?];
+        }
+    }
+
+    /// Diagnostic pointing to the node at path, or its nearest parent.
+    pub fn diagnostic(&self, p: &str, message: &str) -> Diagnostic<'_> {
+        Diagnostic {
+            map: self,
+            span: self.nearest_span(p),
+            message: message.to_string(),
+            label: String::new(),
+        }
+    }
+
+    fn span(&self, range: Range<usize>) -> Span {
+        let before = &self.text[..range.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let col = before[line_start..].chars().count() + 1;
+        Span { range, line, col }
+    }
+}
+
+fn item_spans(item: &toml_edit::Item, prefix: &str, spans: &mut BTreeMap<String, Range<usize>>) {
+    match item {
+        toml_edit::Item::Value(v) => value_spans(v, prefix, spans),
+        toml_edit::Item::Table(table) => {
+            mark_span(table.span(), prefix, spans);
+            for (k, v) in table.iter() {
+                item_spans(v, &join_key(prefix, k), spans);
+            }
+        },
+        toml_edit::Item::ArrayOfTables(array) => {
+            for (i, table) in array.iter().enumerate() {
+                let key = join_key(prefix, &i.to_string());
+                mark_span(table.span(), &key, spans);
+                for (k, v) in table.iter() {
+                    item_spans(v, &join_key(&key, k), spans);
+                }
+            }
+        },
+        toml_edit::Item::None => {},
+    }
+}
+
+fn value_spans(v: &toml_edit::Value, prefix: &str, spans: &mut BTreeMap<String, Range<usize>>) {
+    mark_span(v.span(), prefix, spans);
+    match v {
+        toml_edit::Value::Array(array) => {
+            for (i, v) in array.iter().enumerate() {
+                value_spans(v, &join_key(prefix, &i.to_string()), spans);
+            }
+        },
+        toml_edit::Value::InlineTable(table) => {
+            for (k, v) in table.iter() {
+                value_spans(v, &join_key(prefix, k), spans);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn mark_span(span: Option<Range<usize>>, key: &str, spans: &mut BTreeMap<String, Range<usize>>) {
+    if let Some(span) = span {
+        spans.insert(key.to_string(), span);
+    }
+}
+
+/// Error message pointing to source line, display in rustc style.
+pub struct Diagnostic<'a> {
+    map: &'a SourceMap,
+    span: Option<Span>,
+    message: String,
+    label: String,
+}
+
+impl<'a> Diagnostic<'a> {
+    /// Add label after the underline.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Span that the diagnostic point to.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+}
+
+impl<'a> fmt::Display for Diagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        let Some(span) = &self.span else {
+            return writeln!(f, " --> {}", self.map.name);
+        };
+
+        let text = &self.map.text;
+        let line_start = text[..span.range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[span.range.start..].find('\n').map_or(text.len(), |i| span.range.start + i);
+        let source = text[line_start..line_end].trim_end_matches('\r');
+        let end = span.range.end.min(line_start + source.len()).max(span.range.start);
+        let width = text[span.range.start..end].chars().count().max(1);
+
+        let number = span.line.to_string();
+        let pad = " ".repeat(number.len());
+        writeln!(f, "{}--> {}:{}:{}", pad, self.map.name, span.line, span.col)?;
+        writeln!(f, "{} |", pad)?;
+        writeln!(f, "{} | {}", number, source)?;
+        let marker = format!("{}{}", " ".repeat(span.col - 1), "^".repeat(width));
+        if self.label.is_empty() {
+            writeln!(f, "{} | {}", pad, marker)
+        } else {
+            writeln!(f, "{} | {} {}", pad, marker, self.label)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
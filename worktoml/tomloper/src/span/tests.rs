@@ -0,0 +1,70 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::tests::load_test_text;
+
+fn load_test_map() -> SourceMap
+{
+    let str_toml = load_test_text();
+    return SourceMap::new("sample.toml", str_toml);
+}
+
+#[test]
+fn span_of_test() {
+    let map = load_test_map();
+    let text = map.text();
+
+    let span = map.span_of("service.1.desc").unwrap();
+    assert_eq!((span.line, span.col), (12, 8));
+    assert_eq!(&text[span.range], "\"another server\"");
+
+    let span = map.span_of("host/protocol/2").unwrap();
+    assert_eq!((span.line, span.col), (6, 27));
+    assert_eq!(&text[span.range], "\"mmp\"");
+
+    let span = map.span_of("misc").unwrap();
+    assert_eq!(span.line, 13);
+    let span = map.span_of("service/1").unwrap();
+    assert_eq!(span.line, 10);
+
+    assert_eq!(map.span_of("host.none"), None);
+    assert_eq!(map.nearest_span("host.none").unwrap().line, 3);
+    assert_eq!(map.nearest_span("none"), None);
+}
+
+#[test]
+fn diagnostic_test() {
+    let map = load_test_map();
+    let diag = map.diagnostic("service.1.desc", "description too vague");
+    let expect = "\
+error: description too vague
+  --> sample.toml:12:8
+   |
+12 | desc = \"another server\"
+   |        ^^^^^^^^^^^^^^^^
+";
+    assert_eq!(diag.to_string(), expect);
+
+    let diag = map.diagnostic("host.protocol.1", "unsupported").label("only tcp");
+    assert_eq!(diag.span().unwrap().line, 6);
+    assert_eq!(diag.to_string().ends_with("  |                    ^^^^^ only tcp\n"), true);
+
+    let map = SourceMap::new("bad.toml", "a = ");
+    assert_eq!(map.diagnostic("a", "bad").to_string(), "error: bad\n --> bad.toml\n");
+}
+
+#[test]
+fn multiline_test() {
+    let text = "[a]\nlist = [\n  1,\n  2,\n]\nname = \"中文\"\n";
+    let map = SourceMap::new("m.toml", text);
+    let span = map.span_of("a.list.1").unwrap();
+    assert_eq!((span.line, span.col), (4, 3));
+    let diag = map.diagnostic("a.list", "too long");
+    assert_eq!(diag.to_string().contains("2 | list = [\n  |        ^\n"), true);
+
+    let text = "name = \"中文\" # x\nport = 1\n";
+    let map = SourceMap::new("m.toml", text);
+    assert_eq!(map.span_of("port").unwrap().col, 8);
+    let diag = map.diagnostic("name", "bad name");
+    assert_eq!(diag.to_string().contains("1 | name = \"中文\" # x\n  |        ^^^^\n"), true);
+}
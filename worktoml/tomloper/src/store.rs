@@ -0,0 +1,263 @@
+//! Small key/value store kept in a toml file, with durable atomic writes.
+//!
+//! Each `update()` run the edits as transaction, then write the whole tree to a temp file
+//! in the same directory, sync and rename it over the store file, so that a crash leave
+//! either the old or the new content but never a truncated one.
+//! With `lock` option, the update holds an exclusive lock on `<file>.lock` and reload
+//! the file before editing, so several processes can share the store.
+//! With `backups` option, the previous contents are kept as `<file>.bak.1`, `<file>.bak.2` ...
+//!
+//! ```rust
+//! use tomloper::{PathOperator, StoreOptions, TomlStore};
+//! let file = std::env::temp_dir().join(format!("tomloper-doc-store-{}.toml", std::process::id()));
+//! let options = StoreOptions { lock: true, backups: 2 };
+//!
+//! let mut store = TomlStore::open_with(&file, options).unwrap();
+//! for _ in 0..2 {
+//!     store.update(|v| {
+//!         let runs = v.pathto("runs") | 0;
+//!         let _ = v.path_mut() << ("runs", runs + 1);
+//!         Ok(())
+//!     }).unwrap();
+//! }
+//!
+//! let store = TomlStore::open(&file).unwrap();
+//! assert_eq!(store.pathto("runs") | 0, 2);
+//! # let _ = std::fs::remove_file(&file);
+//! # let _ = std::fs::remove_file(file.with_extension("toml.lock"));
+//! # let _ = std::fs::remove_file(file.with_extension("toml.bak.1"));
+//! ```
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use toml::Value;
+use toml::value::Table;
+
+use crate::{FormatOptions, TxError, format_toml, transaction};
+
+/// Sequence of temp file, unique in the process together with pid.
+static TEMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Options of `TomlStore`.
+#[derive(Debug, Clone, Default)]
+pub struct StoreOptions {
+    /// Lock `<file>.lock` while reading and writing, for multiple processes.
+    pub lock: bool,
+    /// Number of previous contents to keep, 0 for no backup.
+    pub backups: usize,
+}
+
+/// Error of `TomlStore`.
+#[derive(Debug)]
+pub enum StoreError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    /// The edits was rolled back, nothing written.
+    Tx(TxError),
+    /// The new content is written and the tree updated, but fail to keep the backup.
+    Backup(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            StoreError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            StoreError::Tx(e) => write!(f, "{}", e),
+            StoreError::Backup(path, e) => write!(f, "{}: fail to backup: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<TxError> for StoreError {
+    fn from(e: TxError) -> Self {
+        StoreError::Tx(e)
+    }
+}
+
+/// Toml tree persisted in a file, deref to `toml::Value` for reading.
+#[derive(Debug)]
+pub struct TomlStore {
+    path: PathBuf,
+    value: Value,
+    options: StoreOptions,
+}
+
+impl Deref for TomlStore {
+    type Target = Value;
+    fn deref(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl TomlStore {
+    /// Open store file with default options, a missing file is an empty table.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        Self::open_with(path, StoreOptions::default())
+    }
+
+    /// Open store file with options.
+    pub fn open_with<P: AsRef<Path>>(path: P, options: StoreOptions) -> Result<Self, StoreError> {
+        let mut store = Self {
+            path: path.as_ref().to_path_buf(),
+            value: Value::Table(Table::new()),
+            options,
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// The store file path.
+    pub fn file(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file again, discard the in-memory tree.
+    pub fn reload(&mut self) -> Result<(), StoreError> {
+        let _guard = self.lock(false)?;
+        self.value = self.read()?;
+        Ok(())
+    }
+
+    /// Edit the tree as transaction and persist it if succeed.
+    /// With `lock` option the file is reloaded first, so changes of other process are kept.
+    /// The edits are applied to a copy, which replace the tree only after it is written,
+    /// so if the edits or the write fail, neither the tree nor the file is changed.
+    /// Only `StoreError::Backup` is returned after both are changed.
+    pub fn update<F, R>(&mut self, f: F) -> Result<R, StoreError>
+    where F: FnOnce(&mut Value) -> Result<R, TxError>
+    {
+        let _guard = self.lock(true)?;
+        let mut value = if self.options.lock { self.read()? } else { self.value.clone() };
+        let result = transaction(&mut value, f)?;
+        let old = self.write(&value)?;
+        self.value = value;
+        self.rotate(old)?;
+        Ok(result)
+    }
+
+    /// Write the current tree to file, as it is.
+    pub fn save(&self) -> Result<(), StoreError> {
+        let _guard = self.lock(true)?;
+        let old = self.write(&self.value)?;
+        self.rotate(old)
+    }
+
+    /// Path of the n-th backup, 1 is the newest.
+    pub fn backup_file(&self, n: usize) -> PathBuf {
+        sibling(&self.path, &format!("bak.{}", n))
+    }
+
+    fn io_error(&self, path: &Path) -> impl Fn(std::io::Error) -> StoreError {
+        let path = path.to_path_buf();
+        move |e| StoreError::Io(path.clone(), e)
+    }
+
+    fn read(&self) -> Result<Value, StoreError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(text) => text.parse().map_err(|e| StoreError::Parse(self.path.clone(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Value::Table(Table::new())),
+            Err(e) => Err(StoreError::Io(self.path.clone(), e)),
+        }
+    }
+
+    /// Hold shared or exclusive lock until the returned file is dropped,
+    /// `None` if not configured to lock.
+    fn lock(&self, exclusive: bool) -> Result<Option<File>, StoreError> {
+        if !self.options.lock {
+            return Ok(None);
+        }
+        let path = sibling(&self.path, "lock");
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path)
+            .map_err(self.io_error(&path))?;
+        let locked = if exclusive { file.lock() } else { file.lock_shared() };
+        locked.map_err(self.io_error(&path))?;
+        Ok(Some(file))
+    }
+
+    /// Temp file to write, not shared by other store or call even in the same process.
+    fn temp_file(&self) -> PathBuf {
+        let seq = TEMP_SEQ.fetch_add(1, Ordering::Relaxed);
+        sibling(&self.path, &format!("tmp.{}.{}", std::process::id(), seq))
+    }
+
+    /// Write to temp file, sync and rename over the store file,
+    /// return the old content if it should be backup.
+    fn write(&self, value: &Value) -> Result<Option<Vec<u8>>, StoreError> {
+        let text = format_toml(value, &FormatOptions::default());
+        let temp = self.temp_file();
+        let written = File::create(&temp).and_then(|mut file| {
+            file.write_all(text.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&temp);
+            return Err(StoreError::Io(temp, e));
+        }
+
+        // the old content to backup, kept until the new file is in place
+        let old = match self.options.backups {
+            0 => None,
+            _ => match std::fs::read(&self.path) {
+                Ok(old) => Some(old),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&temp);
+                    return Err(StoreError::Io(self.path.clone(), e));
+                },
+            },
+        };
+        if let Err(e) = std::fs::rename(&temp, &self.path) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(StoreError::Io(self.path.clone(), e));
+        }
+        sync_dir(&self.path);
+        Ok(old)
+    }
+
+    /// Shift `bak.n` to `bak.n+1` and write the old content to `bak.1`.
+    fn rotate(&self, old: Option<Vec<u8>>) -> Result<(), StoreError> {
+        let Some(old) = old else {
+            return Ok(());
+        };
+        for n in (1..self.options.backups).rev() {
+            let from = self.backup_file(n);
+            if from.exists() {
+                let to = self.backup_file(n + 1);
+                std::fs::rename(&from, &to).map_err(|e| StoreError::Backup(to, e))?;
+            }
+        }
+        let newest = self.backup_file(1);
+        std::fs::write(&newest, old).map_err(|e| StoreError::Backup(newest, e))?;
+        Ok(())
+    }
+}
+
+/// File beside `path` with extra extension, as `state.toml.lock`.
+fn sibling(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ext);
+    path.with_file_name(name)
+}
+
+/// Make the rename durable, best effort as not all platform can open directory.
+fn sync_dir(path: &Path) {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(test)]
+mod tests;
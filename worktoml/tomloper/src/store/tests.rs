@@ -0,0 +1,155 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+
+/// Store file path unique to each test, with stale files removed.
+fn temp_store(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("tomloper-store-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    return dir.join("state.toml");
+}
+
+#[test]
+fn update_test() {
+    let file = temp_store("update");
+    let mut store = TomlStore::open(&file).unwrap();
+    assert_eq!(store.as_table().unwrap().is_empty(), true);
+    assert_eq!(file.exists(), false);
+
+    let last = store.update(|v| {
+        let _ = v.path_mut() << ("last_run", "2024-01-01") << ("counter", 1);
+        Ok(v.pathto("counter") | 0)
+    }).unwrap();
+    assert_eq!(last, 1);
+
+    let text = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(text, "counter = 1\nlast_run = \"2024-01-01\"\n");
+    let reopen = TomlStore::open(&file).unwrap();
+    assert_eq!(reopen.pathto("last_run") | "", "2024-01-01");
+
+    let entries: Vec<_> = std::fs::read_dir(file.parent().unwrap()).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+}
+
+#[test]
+fn rollback_test() {
+    let file = temp_store("rollback");
+    let mut store = TomlStore::open(&file).unwrap();
+    store.update(|v| {
+        (v.path_mut() << ("counter", 1)).check()?;
+        Ok(())
+    }).unwrap();
+
+    let result = store.update(|v| {
+        (v.pathto_mut("counter") << 2).check()?;
+        (v.pathto_mut("counter") << "two").check()?;
+        Ok(())
+    });
+    assert_eq!(matches!(result, Err(StoreError::Tx(TxError::Invalid))), true);
+    assert_eq!(store.pathto("counter") | 0, 1);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "counter = 1\n");
+}
+
+#[test]
+fn backup_test() {
+    let file = temp_store("backup");
+    let options = StoreOptions { lock: false, backups: 2 };
+    let mut store = TomlStore::open_with(&file, options).unwrap();
+    for i in 1..=4 {
+        store.update(|v| {
+            let _ = v.path_mut() << ("counter", i);
+            Ok(())
+        }).unwrap();
+    }
+
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "counter = 4\n");
+    assert_eq!(std::fs::read_to_string(store.backup_file(1)).unwrap(), "counter = 3\n");
+    assert_eq!(std::fs::read_to_string(store.backup_file(2)).unwrap(), "counter = 2\n");
+    assert_eq!(store.backup_file(3).exists(), false);
+}
+
+#[test]
+fn lock_test() {
+    let file = temp_store("lock");
+    let options = StoreOptions { lock: true, backups: 0 };
+    let threads: Vec<_> = (0..4).map(|_| {
+        let file = file.clone();
+        let options = options.clone();
+        std::thread::spawn(move || {
+            let mut store = TomlStore::open_with(&file, options).unwrap();
+            for _ in 0..10 {
+                store.update(|v| {
+                    let counter = v.pathto("counter") | 0;
+                    let _ = v.path_mut() << ("counter", counter + 1);
+                    Ok(())
+                }).unwrap();
+            }
+        })
+    }).collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    let store = TomlStore::open(&file).unwrap();
+    assert_eq!(store.pathto("counter") | 0, 40);
+}
+
+#[test]
+fn corrupted_test() {
+    let file = temp_store("corrupted");
+    std::fs::write(&file, "counter = ").unwrap();
+    let result = TomlStore::open(&file);
+    assert_eq!(matches!(result, Err(StoreError::Parse(..))), true);
+}
+
+#[test]
+fn write_fail_test() {
+    let file = temp_store("write-fail");
+    let mut store = TomlStore::open(&file).unwrap();
+    store.update(|v| {
+        let _ = v.path_mut() << ("counter", 1);
+        Ok(())
+    }).unwrap();
+
+    // no directory to create temp file
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    let result = store.update(|v| {
+        let _ = v.pathto_mut("counter") << 2;
+        Ok(())
+    });
+    assert_eq!(matches!(result, Err(StoreError::Io(..))), true);
+    assert_eq!(store.pathto("counter") | 0, 1);
+}
+
+#[test]
+fn backup_fail_test() {
+    let file = temp_store("backup-fail");
+    let options = StoreOptions { lock: false, backups: 1 };
+    let mut store = TomlStore::open_with(&file, options).unwrap();
+    store.update(|v| {
+        let _ = v.path_mut() << ("counter", 1);
+        Ok(())
+    }).unwrap();
+
+    // a directory in place of the backup file
+    std::fs::create_dir(store.backup_file(1)).unwrap();
+    let result = store.update(|v| {
+        let _ = v.pathto_mut("counter") << 2;
+        Ok(())
+    });
+    assert_eq!(matches!(result, Err(StoreError::Backup(..))), true);
+    assert_eq!(store.pathto("counter") | 0, 2);
+    assert_eq!(TomlStore::open(&file).unwrap().pathto("counter") | 0, 2);
+}
+
+#[test]
+fn temp_file_test() {
+    let file = temp_store("temp-file");
+    let first = TomlStore::open(&file).unwrap();
+    let second = TomlStore::open(&file).unwrap();
+    let names = [first.temp_file(), first.temp_file(), second.temp_file()];
+    assert_eq!(names[0] != names[1] && names[1] != names[2] && names[0] != names[2], true);
+}
@@ -0,0 +1,178 @@
+//! Push structured entries through operator `<<`, and `toml_table!` macro to build them.
+//!
+//! When the pointer refer to array, the entry is appended as a new table item,
+//! that is a new `[[service]]` for array of tables.
+//! When the pointer refer to table, the key-value pairs are inserted into it.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, toml_table};
+//! let mut v: toml::Value = "[[service]]\nname = \"serv_1\"".parse().unwrap();
+//!
+//! let port = 8080;
+//! let node = v.path_mut() / "service" << toml_table! {
+//!     name = "serv_2",
+//!     port = port + 1,
+//!     protocol = ["tcp", "udp"],
+//!     limit = { conn = 100 },
+//! };
+//! assert_eq!(node / 1 / "port" | 0, 8081);
+//! assert_eq!(v.path() / "service" / 1 / "limit" / "conn" | 0, 100);
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::ops::Shl;
+
+use serde::Serialize;
+use toml::Value;
+
+use crate::TomlPtrMut;
+
+/// Build `toml::value::Table` from `key = value` pairs separated by comma.
+/// The key is identifier or string literal, the value is any expression that
+/// can convert to `toml::Value`, or nested `{ ... }` table and `[ ... ]` array.
+/// It refer `toml` through `tomloper::toml`, so the caller need not depend on `toml`.
+///
+/// ```rust
+/// use tomloper::{PathOperator, toml_table};
+/// let table = toml_table! { name = "serv", ports = [80, 443], tls = { on = true } };
+/// let v = tomloper::toml::Value::Table(table);
+/// assert_eq!(v.pathto("ports/-1") | 0, 443);
+/// assert_eq!(v.pathto("tls/on") | false, true);
+/// ```
+#[macro_export]
+macro_rules! toml_table {
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut table = $crate::toml::value::Table::new();
+        $crate::__toml_entries!(table $($body)*);
+        table
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __toml_entries {
+    ($table:ident) => {};
+    ($table:ident $key:tt = { $($value:tt)* } $(, $($rest:tt)*)?) => {
+        $table.insert($crate::__toml_key!($key), $crate::__toml_value!({ $($value)* }));
+        $crate::__toml_entries!($table $($($rest)*)?);
+    };
+    ($table:ident $key:tt = [ $($value:tt)* ] $(, $($rest:tt)*)?) => {
+        $table.insert($crate::__toml_key!($key), $crate::__toml_value!([ $($value)* ]));
+        $crate::__toml_entries!($table $($($rest)*)?);
+    };
+    ($table:ident $key:tt = $value:expr $(, $($rest:tt)*)?) => {
+        $table.insert($crate::__toml_key!($key), $crate::__toml_value!($value));
+        $crate::__toml_entries!($table $($($rest)*)?);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __toml_key {
+    ($key:ident) => { ::std::string::String::from(stringify!($key)) };
+    ($key:literal) => { ::std::string::String::from($key) };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __toml_value {
+    ({ $($value:tt)* }) => { $crate::toml::Value::Table($crate::toml_table!{ $($value)* }) };
+    ([ $($value:tt)* ]) => { $crate::toml::Value::Array($crate::__toml_array!([] $($value)*)) };
+    ($value:expr) => { $crate::toml::Value::from($value) };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __toml_array {
+    ([$($out:expr,)*]) => { ::std::vec![$($out,)*] };
+    ([$($out:expr,)*] { $($value:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__toml_array!([$($out,)* $crate::__toml_value!({ $($value)* }),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] [ $($value:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__toml_array!([$($out,)* $crate::__toml_value!([ $($value)* ]),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__toml_array!([$($out,)* $crate::__toml_value!($value),] $($($rest)*)?)
+    };
+}
+
+/// Wrapper for any serde serializable value to push with `<<`,
+/// eg: `toml/array/node << Ser(&service)`.
+pub struct Ser<T>(pub T);
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Append entries as a table item to array, or insert them to table.
+    fn push_entries<I>(mut self, entries: I) -> Self
+    where I: IntoIterator<Item = (String, Value)>
+    {
+        if self.valop.is_none() {
+            return Self::none();
+        }
+        let v = self.valop.take().unwrap();
+        match v {
+            Value::Array(array) => {
+                array.push(Value::Table(entries.into_iter().collect()));
+            },
+            Value::Table(table) => {
+                table.extend(entries);
+            },
+            _ => return Self::none(),
+        }
+        return Self::path(v);
+    }
+}
+
+/// Operator `<<` to push `toml::value::Table`, such as built by `toml_table!`.
+impl<'tr> Shl<toml::value::Table> for TomlPtrMut<'tr> {
+    type Output = Self;
+    fn shl(self, rhs: toml::value::Table) -> Self::Output {
+        self.push_entries(rhs)
+    }
+}
+
+/// Operator `<<` to push `BTreeMap` as table.
+impl<'tr, K: ToString, T> Shl<BTreeMap<K, T>> for TomlPtrMut<'tr> where Value: From<T>
+{
+    type Output = Self;
+    fn shl(self, rhs: BTreeMap<K, T>) -> Self::Output {
+        self.push_entries(rhs.into_iter().map(|(k, v)| (k.to_string(), Value::from(v))))
+    }
+}
+
+/// Operator `<<` to push `HashMap` as table.
+impl<'tr, K: ToString, T, S: BuildHasher> Shl<HashMap<K, T, S>> for TomlPtrMut<'tr> where Value: From<T>
+{
+    type Output = Self;
+    fn shl(self, rhs: HashMap<K, T, S>) -> Self::Output {
+        self.push_entries(rhs.into_iter().map(|(k, v)| (k.to_string(), Value::from(v))))
+    }
+}
+
+/// Operator `<<` to push `Vec` of key-value pairs as table.
+/// eg: `toml/array/node << vec![("name", "serv"), ("desc", "new server")]`.
+impl<'tr, K: ToString, T> Shl<Vec<(K, T)>> for TomlPtrMut<'tr> where Value: From<T>
+{
+    type Output = Self;
+    fn shl(self, rhs: Vec<(K, T)>) -> Self::Output {
+        self.push_entries(rhs.into_iter().map(|(k, v)| (k.to_string(), Value::from(v))))
+    }
+}
+
+/// Operator `<<` to push serializable struct as table.
+/// Non-table value can only be pushed to array.
+/// While fail to serialize, set self pointer to `None`.
+impl<'tr, T: Serialize> Shl<Ser<T>> for TomlPtrMut<'tr> {
+    type Output = Self;
+    fn shl(mut self, rhs: Ser<T>) -> Self::Output {
+        match Value::try_from(rhs.0) {
+            Ok(Value::Table(table)) => self.push_entries(table),
+            Ok(value) => self.push_array(value),
+            Err(_) => Self::none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
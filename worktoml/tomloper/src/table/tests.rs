@@ -0,0 +1,87 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_toml;
+
+#[derive(Serialize)]
+struct Service {
+    name: String,
+    desc: String,
+    port: Option<i64>,
+}
+
+#[test]
+fn toml_table_macro_test() {
+    let name = "serv_3";
+    let table = toml_table! {
+        name = name,
+        "the-port" = 8000 + 80,
+        ratio = 0.5,
+        tags = ["a", 1, { k = "v" }, [true]],
+        nested = { inner = { deep = true }, list = [] },
+    };
+    let v = Value::Table(table);
+    assert_eq!(v.path() / "name" | "", "serv_3");
+    assert_eq!(v.path() / "the-port" | 0, 8080);
+    assert_eq!(v.path() / "ratio" | 0.0, 0.5);
+    assert_eq!(v.path() / "tags" / 1 | 0, 1);
+    assert_eq!(v.path() / "tags" / 2 / "k" | "", "v");
+    assert_eq!(v.path() / "tags" / 3 / 0 | false, true);
+    assert_eq!(v.path() / "nested" / "inner" / "deep" | false, true);
+    assert_eq!(v.pathto("nested.list").unpath().unwrap().as_array().unwrap().len(), 0);
+
+    let empty = toml_table!{};
+    assert_eq!(empty.is_empty(), true);
+}
+
+#[test]
+fn push_table_test() {
+    let mut v = load_test_toml();
+
+    let node = v.path_mut() / "service" << toml_table! { name = "serv_3", desc = "third" };
+    assert_eq!(node / 2 / "name" | "", "serv_3");
+
+    let mut map = BTreeMap::new();
+    map.insert("name", "serv_4");
+    let _ = v.path_mut() / "service" << map;
+    assert_eq!(v.pathto("service.3.name") | "", "serv_4");
+
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), 5);
+    let _ = v.path_mut() / "service" << map;
+    assert_eq!(v.pathto("service.4.name") | 0, 5);
+
+    let _ = v.path_mut() / "service" << vec![("name", "serv_6"), ("desc", "sixth")];
+    assert_eq!(v.pathto("service.5.desc") | "", "sixth");
+
+    // into table insert the entries
+    let _ = v.path_mut() / "misc" << vec![("int", 1), ("new", 2)];
+    assert_eq!(v.pathto("misc.int") | 0, 1);
+    assert_eq!(v.pathto("misc.new") | 0, 2);
+    assert_eq!(v.pathto("misc.bool") | false, true);
+
+    // leaf node cannot take table
+    let node = v.path_mut() / "ip" << vec![("k", 1)];
+    assert_eq!(node.is_none(), true);
+}
+
+#[test]
+fn push_serde_test() {
+    let mut v = load_test_toml();
+
+    let serv = Service { name: "serv_3".to_string(), desc: "third".to_string(), port: Some(80) };
+    let node = v.path_mut() / "service" << Ser(&serv);
+    assert_eq!(node / 2 / "port" | 0, 80);
+    assert_eq!(v.pathto("service.2.name") | "", "serv_3");
+
+    let serv = Service { name: "serv_4".to_string(), desc: "".to_string(), port: None };
+    let _ = v.path_mut() / "service" << Ser(serv);
+    assert_eq!(v.pathto("service.3.name") | "", "serv_4");
+    assert_eq!(v.pathto("service.3.port").is_none(), true);
+
+    let _ = v.path_mut() / "host" / "protocol" << Ser(vec!["a", "b"]);
+    assert_eq!(v.pathto("host.protocol.3.1") | "", "b");
+    let node = v.path_mut() / "ip" << Ser("scalar");
+    assert_eq!(node.is_none(), true);
+}
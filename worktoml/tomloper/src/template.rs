@@ -0,0 +1,227 @@
+//! Mini template engine to render text file from toml data.
+//!
+//! * `{{ host.ip }}` is replaced by the node at path, resolved by `pathto()`.
+//! * `{{ host.port | 8080 }}` give default in toml syntax, which mirror the `|` operator:
+//!   the default is used when the node is missing or its type differ from the default.
+//! * `{% for s in service %} ... {% endfor %}` loop over array, then `{{ s.name }}` is
+//!   resolved relative to the item. Missing array loop zero times.
+//!
+//! Scalar is rendered as plain text, string without quotes, while array and table
+//! are rendered as inline toml. A `{% %}` tag alone on its line remove the whole line,
+//! so that loops don't leave blank lines.
+//!
+//! ```rust
+//! use tomloper::Template;
+//! let v: toml::Value = "[host]\nip = \"10.0.0.1\"\n[[service]]\nname = \"api\"\n[[service]]\nname = \"web\"\nport = 81".parse().unwrap();
+//! let tpl = Template::parse("\
+//! listen {{ host.ip }}:{{ host.port | 80 }};
+//! {% for s in service %}
+//! upstream {{ s.name }} {{ s.port | 8000 }};
+//! {% endfor %}
+//! ").unwrap();
+//!
+//! assert_eq!(tpl.render(&v).unwrap(), "\
+//! listen 10.0.0.1:80;
+//! upstream api 8000;
+//! upstream web 81;
+//! ");
+//! ```
+
+use std::fmt;
+
+use toml::Value;
+
+use crate::{PathOperator, TomlPtr};
+use crate::format::format_inline;
+use crate::layered::parse_scalar;
+
+/// Error of parsing or rendering template, with 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// Unclosed or unknown tag.
+    Syntax(usize, String),
+    /// Path not found and no default given.
+    Missing(usize, String),
+    /// Loop over node that is not array.
+    NotArray(usize, String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Syntax(line, msg) => write!(f, "line {}: syntax error: {}", line, msg),
+            TemplateError::Missing(line, p) => write!(f, "line {}: no value for {}", line, p),
+            TemplateError::NotArray(line, p) => write!(f, "line {}: not an array: {}", line, p),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var { path: String, default: Option<Value>, line: usize },
+    For { var: String, path: String, body: Vec<Node>, line: usize },
+}
+
+/// Parsed template that can render many times.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Open `for` block while parsing: loop variable, path, line and the nodes before it.
+type OpenLoop = (String, String, usize, Vec<Node>);
+
+impl Template {
+    /// Parse template text.
+    pub fn parse(text: &str) -> Result<Self, TemplateError> {
+        let mut nodes = Vec::new();
+        let mut loops: Vec<OpenLoop> = Vec::new();
+        let mut pos = 0;
+        while let Some(found) = find_tag(text, pos) {
+            let line = line_of(text, found);
+            let close = if text[found..].starts_with("{{") { "}}" } else { "%}" };
+            let Some(end) = text[found + 2..].find(close).map(|i| found + 2 + i) else {
+                return Err(TemplateError::Syntax(line, format!("unclosed {}", &text[found..found + 2])));
+            };
+            let content = text[found + 2..end].trim();
+            let mut next = end + 2;
+
+            if close == "}}" {
+                push_text(&mut nodes, &text[pos..found]);
+                let (path, default) = match content.split_once('|') {
+                    Some((path, default)) => (path.trim(), Some(parse_scalar(default.trim()))),
+                    None => (content, None),
+                };
+                nodes.push(Node::Var { path: path.to_string(), default, line });
+                pos = next;
+                continue;
+            }
+
+            // standalone block tag take the whole line
+            let line_start = text[..found].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = text[next..].find('\n').map_or(text.len(), |i| next + i);
+            let mut text_end = found;
+            if text[line_start..found].trim().is_empty() && text[next..line_end].trim().is_empty() {
+                text_end = line_start.max(pos);
+                next = (line_end + 1).min(text.len());
+            }
+            push_text(&mut nodes, &text[pos..text_end]);
+            pos = next;
+
+            let words: Vec<&str> = content.split_whitespace().collect();
+            match words[..] {
+                ["for", var, "in", path] => {
+                    let outer = std::mem::take(&mut nodes);
+                    loops.push((var.to_string(), path.to_string(), line, outer));
+                },
+                ["endfor"] => {
+                    let Some((var, path, line, outer)) = loops.pop() else {
+                        return Err(TemplateError::Syntax(line, "endfor without for".to_string()));
+                    };
+                    let body = std::mem::replace(&mut nodes, outer);
+                    nodes.push(Node::For { var, path, body, line });
+                },
+                _ => return Err(TemplateError::Syntax(line, format!("unknown tag {{% {} %}}", content))),
+            }
+        }
+        push_text(&mut nodes, &text[pos..]);
+
+        if let Some((_, _, line, _)) = loops.pop() {
+            return Err(TemplateError::Syntax(line, "for without endfor".to_string()));
+        }
+        Ok(Self { nodes })
+    }
+
+    /// Render with toml data.
+    pub fn render(&self, v: &Value) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        let mut scope = Vec::new();
+        render_nodes(&self.nodes, v, &mut scope, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Parse and render template text at once.
+pub fn render_str(text: &str, v: &Value) -> Result<String, TemplateError> {
+    Template::parse(text)?.render(v)
+}
+
+fn find_tag(text: &str, pos: usize) -> Option<usize> {
+    let var = text[pos..].find("{{");
+    let block = text[pos..].find("{%");
+    let found = match (var, block) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b)?,
+    };
+    Some(pos + found)
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(text.to_string()));
+    }
+}
+
+/// Loop variables in scope, the inner one is the last.
+type Scope<'a> = Vec<(&'a str, &'a Value)>;
+
+fn render_nodes<'a>(nodes: &'a [Node], root: &'a Value, scope: &mut Scope<'a>, out: &mut String) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { path, default, line } => {
+                let found = *lookup(root, scope, path);
+                let value = match (found, default) {
+                    (Some(v), Some(d)) if v.same_type(d) => v,
+                    (Some(v), None) => v,
+                    (_, Some(d)) => d,
+                    (None, None) => return Err(TemplateError::Missing(*line, path.clone())),
+                };
+                out.push_str(&display(value));
+            },
+            Node::For { var, path, body, line } => {
+                let items = match *lookup(root, scope, path) {
+                    None => continue,
+                    Some(Value::Array(items)) => items,
+                    Some(_) => return Err(TemplateError::NotArray(*line, path.clone())),
+                };
+                for item in items {
+                    scope.push((var.as_str(), item));
+                    let result = render_nodes(body, root, scope, out);
+                    scope.pop();
+                    result?;
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Resolve path from the loop variable if its first segment name one, or from root.
+fn lookup<'a>(root: &'a Value, scope: &Scope<'a>, p: &str) -> TomlPtr<'a> {
+    let (head, rest) = p.split_once(['.', '/']).unwrap_or((p, ""));
+    for (var, item) in scope.iter().rev() {
+        if *var == head {
+            return item.pathto(rest);
+        }
+    }
+    root.pathto(p)
+}
+
+fn display(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Array(_) | Value::Table(_) => format_inline(v),
+        _ => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,57 @@
+use super::*;
+use crate::tests::load_test_toml;
+
+#[test]
+fn var_test() {
+    let v = load_test_toml();
+    assert_eq!(render_str("{{ host.ip }}:{{host/port}}", &v).unwrap(), "127.0.1.1:8080");
+    assert_eq!(render_str("{{ misc.float }} {{ misc.bool }}", &v).unwrap(), "3.14 true");
+    assert_eq!(render_str("{{ host.protocol }}", &v).unwrap(), "[\"tcp\", \"udp\", \"mmp\"]");
+    assert_eq!(render_str("{{ host.protocol.1 }}", &v).unwrap(), "udp");
+    assert_eq!(render_str("no tag {", &v).unwrap(), "no tag {");
+
+    assert_eq!(render_str("{{ host.user }}", &v), Err(TemplateError::Missing(1, "host.user".to_string())));
+}
+
+#[test]
+fn default_test() {
+    let v = load_test_toml();
+    assert_eq!(render_str("{{ host.user | \"nobody\" }}", &v).unwrap(), "nobody");
+    assert_eq!(render_str("{{ host.user | nobody }}", &v).unwrap(), "nobody");
+    assert_eq!(render_str("{{ host.port | 80 }}", &v).unwrap(), "8080");
+    // type mismatch use default, as `|` operator
+    assert_eq!(render_str("{{ host.port | \"80\" }}", &v).unwrap(), "80");
+    assert_eq!(render_str("{{ misc.int | 1.5 }}", &v).unwrap(), "1.5");
+}
+
+#[test]
+fn for_test() {
+    let v = load_test_toml();
+    let text = "\
+[Unit]
+  {% for s in service %}
+Service={{ s.name }}{% for p in host.protocol %} {{p}}{% endfor %}
+  {% endfor %}
+{% for x in none %}
+{{ x.name }}
+{% endfor %}
+End";
+    let tpl = Template::parse(text).unwrap();
+    let expect = "\
+[Unit]
+Service=serv_1 tcp udp mmp
+Service=serv_2 tcp udp mmp
+End";
+    assert_eq!(tpl.render(&v).unwrap(), expect);
+
+    let tpl = Template::parse("{% for s in host %}{% endfor %}").unwrap();
+    assert_eq!(tpl.render(&v), Err(TemplateError::NotArray(1, "host".to_string())));
+}
+
+#[test]
+fn syntax_test() {
+    assert_eq!(Template::parse("a\n{{ host").unwrap_err(), TemplateError::Syntax(2, "unclosed {{".to_string()));
+    assert_eq!(Template::parse("{% for s in x %}\n").unwrap_err(), TemplateError::Syntax(1, "for without endfor".to_string()));
+    assert_eq!(Template::parse("\n{% endfor %}").unwrap_err(), TemplateError::Syntax(2, "endfor without for".to_string()));
+    assert_eq!(Template::parse("{% if x %}").unwrap_err().to_string(), "line 1: syntax error: unknown tag {% if x %}");
+}
@@ -1,8 +1,19 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use toml::Value;
+
 use super::*;
+use crate::pointer::path;
+
+/// Text of the sample toml shared by all module tests.
+pub(crate) fn load_test_text() -> &'static str
+{
+    include_str!("../examples/sample.toml")
+}
 
-fn load_test_toml() -> Value
+pub(crate) fn load_test_toml() -> Value
 {
-    let str_toml = include_str!("../examples/sample.toml");
+    let str_toml = load_test_text();
     let v: Value = str_toml.parse().unwrap();
     return v;
 }
@@ -157,6 +168,7 @@ fn path_build_test() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn pipe_test() {
     let v = load_test_toml();
 
@@ -210,6 +222,7 @@ fn pipe_test() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn pipe_mut_test() {
     let mut v = load_test_toml();
 
@@ -422,3 +435,95 @@ fn path_if_test() {
     }
 }
 
+
+#[cfg(feature = "derive")]
+#[derive(TomlView)]
+#[allow(dead_code)]
+struct Server<'a> {
+    /// Address to bind.
+    #[toml(path = "host.ip", default = "0.0.0.0")]
+    ip: &'a str,
+    #[toml(path = "host/port", default = 80)]
+    port: i64,
+    #[toml(path = "host.timeout", default = 1.5)]
+    timeout: f64,
+    #[toml(path = "misc.bool")]
+    enable: bool,
+    #[toml(path = "service.1.name", default = "none")]
+    second: String,
+    #[toml(path = "service.2.name")]
+    third: Option<&'a str>,
+    #[toml(path = "misc.int")]
+    int: Option<i64>,
+    #[toml(path = "host.protocol")]
+    protocol: TomlPtr<'a>,
+    #[toml(path = "service.-1.name")]
+    last: Option<&'a str>,
+}
+
+#[cfg(feature = "derive")]
+#[derive(TomlView)]
+#[allow(dead_code)]
+struct Codes {
+    #[toml(path = "code.404", default = "")]
+    not_found: String,
+    #[toml(path = "list.1", default = 0)]
+    second: i64,
+}
+
+/// Same lifetime name as the view used to have.
+#[cfg(feature = "derive")]
+#[derive(TomlView)]
+#[allow(dead_code)]
+struct Host<'tr> {
+    #[toml(path = "host.ip")]
+    ip: &'tr str,
+    #[toml(path = "host.protocol")]
+    protocol: TomlPtr<'tr>,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_view_test() {
+    let v = load_test_toml();
+
+    let server = Server::view(&v);
+    assert_eq!(server.ip(), "127.0.1.1");
+    assert_eq!(server.port(), 8080);
+    assert_eq!(server.timeout(), 1.5);
+    assert_eq!(server.enable(), true);
+    assert_eq!(server.second(), "serv_2");
+    assert_eq!(server.third(), None);
+    assert_eq!(server.int(), Some(1234));
+    assert_eq!(server.protocol() / 1 | "", "udp");
+    assert_eq!(server.last(), Some("serv_2"));
+
+    let loaded = server.load();
+    assert_eq!(loaded.ip, "127.0.1.1");
+    assert_eq!(loaded.port, 8080);
+
+    // view from sub node, the paths are all missing
+    let server = ServerView::new(v.path() / "misc");
+    assert_eq!(server.ip(), "0.0.0.0");
+    assert_eq!(server.port(), 80);
+    assert_eq!(server.enable(), false);
+    assert_eq!(server.second(), "none");
+    assert_eq!(server.int(), None);
+    assert_eq!(server.protocol().is_none(), true);
+
+    let host = Host::view(&v).load();
+    assert_eq!(host.ip, "127.0.1.1");
+    assert_eq!(host.protocol / 0 | "", "tcp");
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_numeric_key_test() {
+    // numeric segment is table key or array index depend on the node
+    let v: Value = "list = [1, 2]
+[code]
+404 = \"not found\"".parse().unwrap();
+    let codes = Codes::view(&v);
+    assert_eq!(codes.not_found(), "not found");
+    assert_eq!(codes.second(), 2);
+}
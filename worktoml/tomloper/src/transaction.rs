@@ -0,0 +1,120 @@
+//! Transactional batch edits on `toml::Value`, all or nothing.
+//!
+//! The closure does several edits by `TomlPtrMut`, and call `check()` on the pointer
+//! to turn invalid pointer into error, then `?` abort the transaction.
+//! All changes are rolled back when the closure return error or a validator reject the result.
+//!
+//! ```rust
+//! use tomloper::{PathOperator, Transaction, TxError, transaction};
+//! let mut v: toml::Value = "[host]\nip = \"127.0.0.1\"\nport = 8080".parse().unwrap();
+//!
+//! let result = transaction(&mut v, |v| {
+//!     (v.path_mut() / "host" / "port" << 9090).check()?;
+//!     (v.path_mut() / "host" / "ip" << 127).check()?; // type mismatch
+//!     Ok(())
+//! });
+//! assert_eq!(result, Err(TxError::Invalid));
+//! assert_eq!(v.path() / "host" / "port" | 0, 8080);
+//!
+//! let result = Transaction::new(&mut v)
+//!     .validate(|v| match v.path() / "host" / "port" | 0 {
+//!         1..=65535 => Ok(()),
+//!         _ => Err("port out of range".to_string()),
+//!     })
+//!     .run(|v| {
+//!         (v.path_mut() / "host" / "port" << 99999).check()?;
+//!         Ok(())
+//!     });
+//! assert_eq!(result, Err(TxError::Rejected("port out of range".to_string())));
+//! assert_eq!(v.path() / "host" / "port" | 0, 8080);
+//! ```
+
+use std::fmt;
+
+use toml::Value;
+
+use crate::TomlPtrMut;
+
+/// Reason why a transaction is rolled back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxError {
+    /// Some step invalidated the pointer, as `!ptr` is true.
+    Invalid,
+    /// The validator rejected the result with message.
+    Rejected(String),
+    /// Aborted by the edit closure itself with message.
+    Aborted(String),
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::Invalid => write!(f, "invalid toml pointer"),
+            TxError::Rejected(msg) => write!(f, "rejected: {}", msg),
+            TxError::Aborted(msg) => write!(f, "aborted: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Turn invalid pointer into `TxError::Invalid`, to abort transaction by `?`.
+    pub fn check(self) -> Result<Self, TxError> {
+        if self.valop.is_none() {
+            return Err(TxError::Invalid);
+        }
+        Ok(self)
+    }
+}
+
+/// Validator run on the edited tree before commit.
+type Validator<'a> = Box<dyn Fn(&Value) -> Result<(), String> + 'a>;
+
+/// Transaction builder over a `toml::Value`, to add validators before run.
+pub struct Transaction<'a> {
+    target: &'a mut Value,
+    validators: Vec<Validator<'a>>,
+}
+
+impl<'a> Transaction<'a> {
+    /// Start a transaction on the toml tree.
+    pub fn new(target: &'a mut Value) -> Self {
+        Self { target, validators: Vec::new() }
+    }
+
+    /// Add a validator, which return error message to reject the result.
+    pub fn validate<V>(mut self, check: V) -> Self
+    where V: Fn(&Value) -> Result<(), String> + 'a
+    {
+        self.validators.push(Box::new(check));
+        self
+    }
+
+    /// Run the edits and validators, roll back if any of them fail.
+    pub fn run<F, R>(self, f: F) -> Result<R, TxError>
+    where F: FnOnce(&mut Value) -> Result<R, TxError>
+    {
+        let backup = self.target.clone();
+        let result = f(self.target).and_then(|r| {
+            for check in &self.validators {
+                check(self.target).map_err(TxError::Rejected)?;
+            }
+            Ok(r)
+        });
+        if result.is_err() {
+            *self.target = backup;
+        }
+        return result;
+    }
+}
+
+/// Run the edits on `v` as a transaction without validator.
+pub fn transaction<F, R>(v: &mut Value, f: F) -> Result<R, TxError>
+where F: FnOnce(&mut Value) -> Result<R, TxError>
+{
+    Transaction::new(v).run(f)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+use super::*;
+use crate::PathOperator;
+use crate::tests::load_test_toml;
+
+#[test]
+fn commit_test() {
+    let mut v = load_test_toml();
+
+    let count = transaction(&mut v, |v| {
+        (v.path_mut() / "host" / "port" << 9090).check()?;
+        (v.path_mut() / "host" / "protocol" << ("quic",)).check()?;
+        let node = (v.path_mut() / "service").check()?;
+        let node = node - 0;
+        Ok(node.unpath().as_ref().unwrap().as_array().unwrap().len())
+    });
+    assert_eq!(count, Ok(1));
+    assert_eq!(v.path() / "host" / "port" | 0, 9090);
+    assert_eq!(v.path() / "host" / "protocol" / 3 | "", "quic");
+    assert_eq!(v.path() / "service" / 0 / "name" | "", "serv_2");
+}
+
+#[test]
+fn rollback_test() {
+    let mut v = load_test_toml();
+    let origin = v.clone();
+
+    // a failed << in the middle of chain
+    let result = transaction(&mut v, |v| {
+        (v.path_mut() / "misc" << ("new", 1) << 5).check()?;
+        Ok(())
+    });
+    assert_eq!(result, Err(TxError::Invalid));
+    assert_eq!(v, origin);
+
+    let result: Result<(), TxError> = transaction(&mut v, |v| {
+        (v.path_mut() / "ip" << "127.0.0.2").check()?;
+        Err(TxError::Aborted("changed mind".to_string()))
+    });
+    assert_eq!(result.unwrap_err().to_string(), "aborted: changed mind");
+    assert_eq!(v, origin);
+
+    let result = Transaction::new(&mut v)
+        .validate(|v| if (v.path() / "misc" / "int" | 0) < 2000 { Ok(()) } else { Err("too big".to_string()) })
+        .validate(|v| if (v.path() / "ip").is_some() { Ok(()) } else { Err("ip required".to_string()) })
+        .run(|v| {
+            let mut ip = (v.path_mut() / "ip").check()?;
+            ip <<= 0;
+            (v.path_mut() / "misc" / "int" << 1999).check()?;
+            Ok(())
+        });
+    assert_eq!(result, Ok(()));
+    assert_eq!(v.path() / "misc" / "int" | 0, 1999);
+    assert_eq!(v.path() / "ip" | 1, 0);
+
+    let result = Transaction::new(&mut v)
+        .validate(|v| if (v.path() / "misc" / "int" | 0) < 2000 { Ok(()) } else { Err("too big".to_string()) })
+        .run(|v| {
+            (v.path_mut() / "misc" / "int" << 2001).check()?;
+            Ok(())
+        });
+    assert_eq!(result, Err(TxError::Rejected("too big".to_string())));
+    assert_eq!(v.path() / "misc" / "int" | 0, 1999);
+}
@@ -0,0 +1,239 @@
+//! Core path engine, `no_std` with `alloc`, generic over any value tree.
+//!
+//! The path syntax is the same as `pathto()`: segments split on slash(/) or dot(.),
+//! numeric segment is array index, negative one count from the end.
+//! Implement `ValueTree` for your own value type to resolve path on it,
+//! `toml::Value` implement it when the `std` feature is enabled.
+//!
+//! ```rust
+//! use tomloper::{ValueTree, resolve};
+//!
+//! enum Conf {
+//!     Int(i64),
+//!     List(Vec<Conf>),
+//!     Map(Vec<(&'static str, Conf)>),
+//! }
+//!
+//! impl ValueTree for Conf {
+//!     fn child(&self, key: &str) -> Option<&Self> {
+//!         match self {
+//!             Conf::Map(map) => map.iter().find(|kv| kv.0 == key).map(|kv| &kv.1),
+//!             _ => None,
+//!         }
+//!     }
+//!     fn child_mut(&mut self, key: &str) -> Option<&mut Self> {
+//!         match self {
+//!             Conf::Map(map) => map.iter_mut().find(|kv| kv.0 == key).map(|kv| &mut kv.1),
+//!             _ => None,
+//!         }
+//!     }
+//!     fn item(&self, index: usize) -> Option<&Self> {
+//!         match self {
+//!             Conf::List(list) => list.get(index),
+//!             _ => None,
+//!         }
+//!     }
+//!     fn item_mut(&mut self, index: usize) -> Option<&mut Self> {
+//!         match self {
+//!             Conf::List(list) => list.get_mut(index),
+//!             _ => None,
+//!         }
+//!     }
+//!     fn array_len(&self) -> Option<usize> {
+//!         match self {
+//!             Conf::List(list) => Some(list.len()),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let conf = Conf::Map(vec![("uart", Conf::Map(vec![("baud", Conf::List(vec![Conf::Int(9600), Conf::Int(115200)]))]))]);
+//! assert!(matches!(resolve(&conf, "uart/baud/-1"), Some(Conf::Int(115200))));
+//! assert!(resolve(&conf, "uart.parity").is_none());
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Minimal value tree that path can be resolved on.
+/// Table node has children by key, array node has items by index,
+/// and any other node is leaf that return `None` for both.
+pub trait ValueTree {
+    /// Child of table node by key.
+    fn child(&self, key: &str) -> Option<&Self>;
+
+    /// Mutable child of table node by key.
+    fn child_mut(&mut self, key: &str) -> Option<&mut Self>;
+
+    /// Item of array node by index.
+    fn item(&self, index: usize) -> Option<&Self>;
+
+    /// Mutable item of array node by index.
+    fn item_mut(&mut self, index: usize) -> Option<&mut Self>;
+
+    /// Length if it is array node, `None` for table and leaf.
+    fn array_len(&self) -> Option<usize>;
+}
+
+/// Resolve path string in the tree, `None` if not exist.
+pub fn resolve<'tr, T: ValueTree>(v: &'tr T, p: &str) -> Option<&'tr T> {
+    p.build_path().apply(v)
+}
+
+/// Resolve path string in the mutable tree, `None` if not exist.
+pub fn resolve_mut<'tr, T: ValueTree>(v: &'tr mut T, p: &str) -> Option<&'tr mut T> {
+    p.build_path().apply_mut(v)
+}
+
+/// Path segment break on slash(/) or dot(.).
+/// eg: `table.subtable.key` or `table/subtable/key` or `array/index/key`
+pub(crate) struct PathSegment
+{
+    pub(crate) paths: Vec<String>,
+}
+
+impl PathSegment
+{
+    /// Resolve path readonly for readonly value tree.
+    pub(crate) fn apply<'tr, T: ValueTree>(&self, v: &'tr T) -> Option<&'tr T> {
+        let mut target = v;
+        for p in &self.paths {
+            if p.is_empty() {
+                continue;
+            }
+            target = step(target, p)?;
+        }
+        return Some(target);
+    }
+
+    /// Resolve path for mutable value tree.
+    pub(crate) fn apply_mut<'tr, T: ValueTree>(&self, v: &'tr mut T) -> Option<&'tr mut T> {
+        let mut target = v;
+        for p in &self.paths {
+            if p.is_empty() {
+                continue;
+            }
+            target = step_mut(target, p)?;
+        }
+        return Some(target);
+    }
+
+    /// Join the non-empty segments with dot, as canonical key of the path.
+    #[cfg(feature = "std")]
+    pub(crate) fn to_key(&self) -> String {
+        let keys: Vec<&str> = self.paths.iter()
+            .filter(|p| !p.is_empty())
+            .map(|p| p.as_str())
+            .collect();
+        keys.join(".")
+    }
+}
+
+/// One segment step down the tree, index for array node and key for others.
+pub(crate) fn step<'tr, T: ValueTree>(v: &'tr T, p: &str) -> Option<&'tr T> {
+    match v.array_len() {
+        Some(len) => v.item(array_index(p, len)?),
+        None => v.child(p),
+    }
+}
+
+/// Mutable version of `step()`.
+pub(crate) fn step_mut<'tr, T: ValueTree>(v: &'tr mut T, p: &str) -> Option<&'tr mut T> {
+    match v.array_len() {
+        Some(len) => v.item_mut(array_index(p, len)?),
+        None => v.child_mut(p),
+    }
+}
+
+/// Parse array index in path, negative index count from the end as `-1` for the last.
+pub(crate) fn array_index(p: &str, len: usize) -> Option<usize> {
+    let index = p.parse::<isize>().ok()?;
+    if index < 0 {
+        return len.checked_sub(index.unsigned_abs());
+    }
+    Some(index as usize)
+}
+
+/// Join dotted path key and sub key, as the inverse of `to_key()`.
+#[cfg(feature = "std")]
+pub(crate) fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        return key.to_string();
+    }
+    alloc::format!("{}.{}", prefix, key)
+}
+
+/// Type trait that can build `PathSegment` from.
+pub(crate) trait PathBuilder {
+    fn build_path(&self) -> PathSegment {
+        PathSegment { paths: Vec::new() }
+    }
+}
+
+/// split string to get path segment vector.
+impl PathBuilder for &str {
+    fn build_path(&self) -> PathSegment {
+        let paths = self
+            .split(|c| c == '/' || c == '.')
+            .map(|s| s.to_string())
+            .collect();
+        PathSegment { paths }
+    }
+}
+
+/// usize index only act path on it's own, but cannot split to more path segment.
+impl PathBuilder for usize {}
+
+#[cfg(feature = "std")]
+impl ValueTree for toml::Value {
+    fn child(&self, key: &str) -> Option<&Self> {
+        self.as_table()?.get(key)
+    }
+
+    fn child_mut(&mut self, key: &str) -> Option<&mut Self> {
+        self.as_table_mut()?.get_mut(key)
+    }
+
+    fn item(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn item_mut(&mut self, index: usize) -> Option<&mut Self> {
+        self.as_array_mut()?.get_mut(index)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.as_array().map(|array| array.len())
+    }
+}
+
+/// The node of `toml_edit` document, so that it is edited by the same path as `toml::Value`.
+#[cfg(feature = "std")]
+impl ValueTree for toml_edit::Item {
+    fn child(&self, key: &str) -> Option<&Self> {
+        self.as_table_like()?.get(key)
+    }
+
+    fn child_mut(&mut self, key: &str) -> Option<&mut Self> {
+        // not `Item::get_mut()`, which insert missing key
+        self.as_table_like_mut()?.get_mut(key)
+    }
+
+    fn item(&self, index: usize) -> Option<&Self> {
+        self.get(index)
+    }
+
+    fn item_mut(&mut self, index: usize) -> Option<&mut Self> {
+        self.get_mut(index)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        match self {
+            toml_edit::Item::ArrayOfTables(array) => Some(array.len()),
+            _ => self.as_array().map(|array| array.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,102 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+
+/// Tiny tree without toml, as what an embedded target may have.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Leaf(i32),
+    List(Vec<Node>),
+    Map(Vec<(String, Node)>),
+}
+
+impl ValueTree for Node {
+    fn child(&self, key: &str) -> Option<&Self> {
+        match self {
+            Node::Map(map) => map.iter().find(|kv| kv.0 == key).map(|kv| &kv.1),
+            _ => None,
+        }
+    }
+
+    fn child_mut(&mut self, key: &str) -> Option<&mut Self> {
+        match self {
+            Node::Map(map) => map.iter_mut().find(|kv| kv.0 == key).map(|kv| &mut kv.1),
+            _ => None,
+        }
+    }
+
+    fn item(&self, index: usize) -> Option<&Self> {
+        match self {
+            Node::List(list) => list.get(index),
+            _ => None,
+        }
+    }
+
+    fn item_mut(&mut self, index: usize) -> Option<&mut Self> {
+        match self {
+            Node::List(list) => list.get_mut(index),
+            _ => None,
+        }
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        match self {
+            Node::List(list) => Some(list.len()),
+            _ => None,
+        }
+    }
+}
+
+fn sample_tree() -> Node
+{
+    let pins = Node::List(vec![Node::Leaf(2), Node::Leaf(3), Node::Leaf(5)]);
+    let gpio = Node::Map(vec![("pins".to_string(), pins), ("1".to_string(), Node::Leaf(1))]);
+    return Node::Map(vec![("gpio".to_string(), gpio)]);
+}
+
+#[test]
+fn resolve_test() {
+    let tree = sample_tree();
+    assert_eq!(resolve(&tree, "gpio/pins/0"), Some(&Node::Leaf(2)));
+    assert_eq!(resolve(&tree, "gpio.pins.-1"), Some(&Node::Leaf(5)));
+    assert_eq!(resolve(&tree, "/gpio//pins/"), resolve(&tree, "gpio.pins"));
+    assert_eq!(resolve(&tree, ""), Some(&tree));
+    // numeric key of table
+    assert_eq!(resolve(&tree, "gpio/1"), Some(&Node::Leaf(1)));
+
+    assert_eq!(resolve(&tree, "gpio/pins/3"), None);
+    assert_eq!(resolve(&tree, "gpio/pins/-4"), None);
+    assert_eq!(resolve(&tree, "gpio/pins/x"), None);
+    assert_eq!(resolve(&tree, "gpio/pins/0/deep"), None);
+}
+
+#[test]
+fn resolve_mut_test() {
+    let mut tree = sample_tree();
+    *resolve_mut(&mut tree, "gpio/pins/-1").unwrap() = Node::Leaf(7);
+    assert_eq!(resolve(&tree, "gpio/pins/2"), Some(&Node::Leaf(7)));
+    assert_eq!(resolve_mut(&mut tree, "gpio/none").is_none(), true);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn toml_tree_test() {
+    let mut v = crate::tests::load_test_toml();
+    assert_eq!(resolve(&v, "host/protocol/-1").and_then(|p| p.as_str()), Some("mmp"));
+    assert_eq!(resolve(&v, "service.1.name").and_then(|p| p.as_str()), Some("serv_2"));
+    *resolve_mut(&mut v, "host/port").unwrap() = toml::Value::from(9090);
+    assert_eq!(v["host"]["port"].as_integer(), Some(9090));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn toml_edit_tree_test() {
+    let mut doc: toml_edit::DocumentMut = crate::tests::load_test_text().parse().unwrap();
+    let root = doc.as_item_mut();
+    assert_eq!(resolve(root, "service/-1/name").and_then(|p| p.as_str()), Some("serv_2"));
+    assert_eq!(resolve(root, "host.protocol.0").and_then(|p| p.as_str()), Some("tcp"));
+    assert_eq!(resolve(root, "host/none").is_none(), true);
+    assert_eq!(resolve_mut(root, "host/none").is_none(), true);
+    // missing key is not inserted by lookup
+    assert_eq!(root["host"].as_table().unwrap().contains_key("none"), false);
+}
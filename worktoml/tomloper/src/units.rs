@@ -0,0 +1,311 @@
+//! Read duration and byte size with units, such as `timeout = "1m30s"` and `cache = "512MiB"`.
+//!
+//! Pipe operator `|` with `Duration` or `ByteSize` default parse the node from string
+//! with units, or integer as seconds and bytes. Operator `<<` write them back as canonical
+//! string, when the node is string or number. `duration_at()` and `bytes_at()` report
+//! bad unit with the path instead of falling back to default.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use tomloper::{ByteSize, PathOperator, UnitValue};
+//! let mut v: toml::Value = "timeout = \"1m30s\"\nretry = 5\ncache = \"512MiB\"\nbad = \"3 parsecs\"".parse().unwrap();
+//!
+//! assert_eq!(v.pathto("timeout") | Duration::ZERO, Duration::from_secs(90));
+//! assert_eq!(v.pathto("retry") | Duration::ZERO, Duration::from_secs(5));
+//! assert_eq!(v.pathto("cache") | ByteSize(0), ByteSize(512 << 20));
+//! assert_eq!(v.pathto("bad") | Duration::ZERO, Duration::ZERO);
+//! assert_eq!(v.duration_at("bad").unwrap_err().to_string(), "bad: bad duration \"3 parsecs\"");
+//!
+//! let _ = v.pathto_mut("retry") << Duration::from_millis(2500);
+//! assert_eq!(v.pathto("retry") | "", "2s500ms");
+//! let _ = v.pathto_mut("cache") << ByteSize(1 << 30);
+//! assert_eq!(v.pathto("cache") | "", "1GiB");
+//! ```
+
+use std::fmt;
+use std::ops::{BitOr, Shl};
+use std::str::FromStr;
+use std::time::Duration;
+
+use toml::Value;
+
+use crate::{PathOperator, TomlPtr, TomlPtrMut};
+
+/// Byte size, display in canonical form as `512MiB`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+/// Error of reading value with units, with the dotted path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitError {
+    /// No value at path.
+    Missing(String),
+    /// The value is not duration, with its text.
+    BadDuration(String, String),
+    /// The value is not byte size, with its text.
+    BadSize(String, String),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::Missing(p) => write!(f, "{}: missing", p),
+            UnitError::BadDuration(p, raw) => write!(f, "{}: bad duration {}", p, raw),
+            UnitError::BadSize(p, raw) => write!(f, "{}: bad byte size {}", p, raw),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+/// Read value with units at path, with error instead of default.
+pub trait UnitValue {
+    fn duration_at(&self, p: &str) -> Result<Duration, UnitError>;
+    fn bytes_at(&self, p: &str) -> Result<ByteSize, UnitError>;
+}
+
+impl UnitValue for Value {
+    fn duration_at(&self, p: &str) -> Result<Duration, UnitError> {
+        let v = self.pathto(p).ok_or_else(|| UnitError::Missing(p.to_string()))?;
+        to_duration(v).ok_or_else(|| UnitError::BadDuration(p.to_string(), v.to_string()))
+    }
+
+    fn bytes_at(&self, p: &str) -> Result<ByteSize, UnitError> {
+        let v = self.pathto(p).ok_or_else(|| UnitError::Missing(p.to_string()))?;
+        to_byte_size(v).ok_or_else(|| UnitError::BadSize(p.to_string(), v.to_string()))
+    }
+}
+
+/// Nanoseconds of duration units, canonical ones first from large to small.
+const DURATION_UNITS: [(&str, u128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+fn duration_unit(unit: &str) -> Option<u128> {
+    let canonical = match unit {
+        "d" | "day" | "days" => "d",
+        "h" | "hr" | "hour" | "hours" => "h",
+        "m" | "min" | "mins" | "minute" | "minutes" => "m",
+        "s" | "sec" | "secs" | "second" | "seconds" => "s",
+        "ms" | "msec" | "millis" => "ms",
+        "us" | "µs" | "usec" | "micros" => "us",
+        "ns" | "nsec" | "nanos" => "ns",
+        _ => return None,
+    };
+    DURATION_UNITS.iter().find(|u| u.0 == canonical).map(|u| u.1)
+}
+
+/// Parse duration as `90s`, `1h30m`, `1.5h` or `250 ms`, plain number is seconds.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let nanos = parse_units(s, duration_unit)?;
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+/// Canonical string of duration, as `1h30m` or `2s500ms`.
+pub fn format_duration(d: Duration) -> String {
+    let mut nanos = d.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let mut out = String::new();
+    for (unit, scale) in DURATION_UNITS {
+        if nanos >= scale {
+            out.push_str(&format!("{}{}", nanos / scale, unit));
+            nanos %= scale;
+        }
+    }
+    return out;
+}
+
+/// Bytes of size units, canonical binary ones first.
+const SIZE_UNITS: [(&str, u64); 9] = [
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+/// Single letter `K`, `M`, `G`, `T` is binary, as most config do.
+fn size_unit(unit: &str) -> Option<u128> {
+    let unit = unit.to_ascii_lowercase();
+    let scale: u64 = match unit.as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return None,
+    };
+    Some(scale as u128)
+}
+
+impl FromStr for ByteSize {
+    type Err = ();
+    /// Parse size as `512MiB`, `1.5 GB` or `64k`, case insensitive, plain number is bytes.
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let bytes = parse_units(s.trim(), size_unit).ok_or(())?;
+        u64::try_from(bytes).map(ByteSize).map_err(|_| ())
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Use the largest unit that divide it exactly, binary unit first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0B");
+        }
+        let (unit, scale) = SIZE_UNITS.iter().find(|u| self.0.is_multiple_of(u.1)).unwrap_or(&("B", 1));
+        write!(f, "{}{}", self.0 / scale, unit)
+    }
+}
+
+impl From<ByteSize> for Value {
+    fn from(size: ByteSize) -> Self {
+        Value::String(size.to_string())
+    }
+}
+
+/// Sum of `number unit` components, fraction is allowed. Empty unit is only allowed
+/// for single component, and it is up to `unit_scale` to accept it.
+fn parse_units<F>(s: &str, unit_scale: F) -> Option<u128>
+where F: Fn(&str) -> Option<u128>
+{
+    if s.is_empty() {
+        return None;
+    }
+    let mut total: u128 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let num_end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let number = &rest[..num_end];
+        rest = rest[num_end..].trim_start();
+        let unit_end = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+        let unit = &rest[..unit_end];
+        rest = rest[unit_end..].trim_start();
+        if number.is_empty() || (unit.is_empty() && total > 0) {
+            return None;
+        }
+        let scale = unit_scale(unit)?;
+        let amount = match number.parse::<u128>() {
+            Ok(n) => n.checked_mul(scale)?,
+            Err(_) => {
+                let n = number.parse::<f64>().ok()? * scale as f64;
+                if !n.is_finite() || n >= u128::MAX as f64 {
+                    return None;
+                }
+                n.round() as u128
+            },
+        };
+        total = total.checked_add(amount)?;
+        if unit.is_empty() && !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(total)
+}
+
+fn to_duration(v: &Value) -> Option<Duration> {
+    match v {
+        Value::String(s) => parse_duration(s),
+        Value::Integer(n) => Some(Duration::from_secs(u64::try_from(*n).ok()?)),
+        Value::Float(f) => Duration::try_from_secs_f64(*f).ok(),
+        _ => None,
+    }
+}
+
+fn to_byte_size(v: &Value) -> Option<ByteSize> {
+    match v {
+        Value::String(s) => s.parse().ok(),
+        Value::Integer(n) => Some(ByteSize(u64::try_from(*n).ok()?)),
+        _ => None,
+    }
+}
+
+/// Pipe operator to get duration from string with units or number of seconds,
+/// or `rhs` as default if missing or bad unit.
+impl<'tr> BitOr<Duration> for TomlPtr<'tr>
+{
+    type Output = Duration;
+    fn bitor(self, rhs: Duration) -> Self::Output {
+        self.valop.and_then(to_duration).unwrap_or(rhs)
+    }
+}
+
+/// Pipe operator to get byte size from string with units or number of bytes,
+/// or `rhs` as default if missing or bad unit.
+impl<'tr> BitOr<ByteSize> for TomlPtr<'tr>
+{
+    type Output = ByteSize;
+    fn bitor(self, rhs: ByteSize) -> Self::Output {
+        self.valop.and_then(to_byte_size).unwrap_or(rhs)
+    }
+}
+
+/// Pipe operator to get duration or `rhs` as default.
+impl<'tr> BitOr<Duration> for TomlPtrMut<'tr>
+{
+    type Output = Duration;
+    fn bitor(self, rhs: Duration) -> Self::Output {
+        self.valop.and_then(|v| to_duration(v)).unwrap_or(rhs)
+    }
+}
+
+/// Pipe operator to get byte size or `rhs` as default.
+impl<'tr> BitOr<ByteSize> for TomlPtrMut<'tr>
+{
+    type Output = ByteSize;
+    fn bitor(self, rhs: ByteSize) -> Self::Output {
+        self.valop.and_then(|v| to_byte_size(v)).unwrap_or(rhs)
+    }
+}
+
+impl<'tr> TomlPtrMut<'tr> {
+    /// Put canonical string to node that is string or number, or invalidate the pointer.
+    fn put_unit_string(mut self, rhs: String) -> Self {
+        match self.valop.take() {
+            Some(v) if v.is_str() || v.is_integer() || v.is_float() => Self::put_val(v, rhs),
+            _ => Self::none(),
+        }
+    }
+}
+
+/// Operator `<<` to put duration as canonical string such as `1m30s`.
+/// While the node is not string or number, set self pointer to `None`.
+impl<'tr> Shl<Duration> for TomlPtrMut<'tr> {
+    type Output = Self;
+    fn shl(self, rhs: Duration) -> Self::Output {
+        self.put_unit_string(format_duration(rhs))
+    }
+}
+
+/// Operator `<<` to put byte size as canonical string such as `512MiB`.
+/// While the node is not string or number, set self pointer to `None`.
+impl<'tr> Shl<ByteSize> for TomlPtrMut<'tr> {
+    type Output = Self;
+    fn shl(self, rhs: ByteSize) -> Self::Output {
+        self.put_unit_string(rhs.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+
+#[test]
+fn duration_test() {
+    assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("1h 30 min"), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+    assert_eq!(parse_duration("2 days"), Some(Duration::from_secs(172_800)));
+    assert_eq!(parse_duration("10µs"), Some(Duration::from_micros(10)));
+    assert_eq!(parse_duration("30"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_duration("0.5"), Some(Duration::from_millis(500)));
+
+    assert_eq!(parse_duration(""), None);
+    assert_eq!(parse_duration("1h30"), None);
+    assert_eq!(parse_duration("3 parsecs"), None);
+    assert_eq!(parse_duration("-5s"), None);
+    assert_eq!(parse_duration("h"), None);
+
+    assert_eq!(format_duration(Duration::ZERO), "0s");
+    assert_eq!(format_duration(Duration::from_secs(5400)), "1h30m");
+    assert_eq!(format_duration(Duration::from_secs(90061)), "1d1h1m1s");
+    assert_eq!(format_duration(Duration::from_nanos(1_500_001)), "1ms500us1ns");
+    let d = Duration::from_millis(123_456_789);
+    assert_eq!(parse_duration(&format_duration(d)), Some(d));
+}
+
+#[test]
+fn byte_size_test() {
+    assert_eq!("512MiB".parse(), Ok(ByteSize(512 << 20)));
+    assert_eq!("512 mib".parse(), Ok(ByteSize(512 << 20)));
+    assert_eq!("64k".parse(), Ok(ByteSize(64 << 10)));
+    assert_eq!("1.5GB".parse(), Ok(ByteSize(1_500_000_000)));
+    assert_eq!("1.5KiB".parse(), Ok(ByteSize(1536)));
+    assert_eq!("100".parse(), Ok(ByteSize(100)));
+    assert_eq!("100B".parse(), Ok(ByteSize(100)));
+    assert_eq!("12 bits".parse::<ByteSize>(), Err(()));
+    assert_eq!("99999999TiB".parse::<ByteSize>(), Err(()));
+
+    assert_eq!(ByteSize(0).to_string(), "0B");
+    assert_eq!(ByteSize(512 << 20).to_string(), "512MiB");
+    assert_eq!(ByteSize(1_000_000).to_string(), "1MB");
+    assert_eq!(ByteSize(1536).to_string(), "1536B");
+    assert_eq!(ByteSize(3 << 40).to_string(), "3TiB");
+}
+
+#[test]
+fn operator_test() {
+    let mut v: Value = "[server]\ntimeout = \"30s\"\nidle = 1.5\ncache = 4096\nname = \"web\"\nflag = true".parse().unwrap();
+    assert_eq!(v.pathto("server/timeout") | Duration::ZERO, Duration::from_secs(30));
+    assert_eq!(v.pathto("server/idle") | Duration::ZERO, Duration::from_millis(1500));
+    assert_eq!(v.pathto("server/none") | Duration::from_secs(1), Duration::from_secs(1));
+    assert_eq!(v.pathto("server/cache") | ByteSize(0), ByteSize(4096));
+    assert_eq!(v.pathto_mut("server/timeout") | Duration::ZERO, Duration::from_secs(30));
+    assert_eq!(v.pathto_mut("server/cache") | ByteSize(0), ByteSize(4096));
+
+    assert_eq!(v.duration_at("server.timeout"), Ok(Duration::from_secs(30)));
+    assert_eq!(v.duration_at("server.name"), Err(UnitError::BadDuration("server.name".to_string(), "\"web\"".to_string())));
+    assert_eq!(v.bytes_at("server/flag").unwrap_err().to_string(), "server/flag: bad byte size true");
+    assert_eq!(v.bytes_at("server.none"), Err(UnitError::Missing("server.none".to_string())));
+
+    let node = v.pathto_mut("server/cache") << ByteSize(8 << 20);
+    assert_eq!(!node, false);
+    assert_eq!(v.pathto("server/cache") | "", "8MiB");
+    let node = v.pathto_mut("server/idle") << Duration::from_secs(120);
+    assert_eq!(!node, false);
+    assert_eq!(v.pathto("server/idle") | "", "2m");
+    let node = v.pathto_mut("server/flag") << Duration::from_secs(1);
+    assert_eq!(!node, true);
+    assert_eq!(v.pathto("server/flag") | false, true);
+
+    let mut node = v.pathto_mut("server/flag");
+    node <<= ByteSize(1024);
+    assert_eq!(v.pathto("server/flag") | "", "1KiB");
+}
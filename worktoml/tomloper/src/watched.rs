@@ -0,0 +1,247 @@
+//! Toml config file that can be reloaded while running, by polling the file in background.
+//!
+//! Each reload parse the file into a new tree and swap the shared `Arc<toml::Value>`,
+//! so the readers that hold the old snapshot are not disturbed.
+//! The registered callbacks are notified with the paths that changed,
+//! in the same order as the reloads even if they run in several threads.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tomloper::{PathOperator, WatchedConfig};
+//!
+//! let mut config = WatchedConfig::open("server.toml").unwrap();
+//! config.on_change(|_v, changed| println!("changed: {:?}", changed));
+//! config.watch(Duration::from_secs(1));
+//!
+//! let port = config.load().path() / "host" / "port" | 8080;
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use toml::Value;
+
+use crate::join_key;
+
+/// Callback invoked after reload, with the new tree and the changed paths.
+/// Shared so that the list can be cloned out of the lock before calling.
+type ChangeHook = Arc<dyn Fn(&Value, &[String]) + Send + Sync>;
+
+/// Error when read the watched file.
+#[derive(Debug)]
+pub enum WatchError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            WatchError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+/// Reloads waiting to notify the hooks, in reload order.
+#[derive(Default)]
+struct Pending {
+    queue: VecDeque<(Arc<Value>, Vec<String>)>,
+    /// Some thread is calling the hooks, and will take the queued ones too.
+    notifying: bool,
+}
+
+/// State shared with the polling thread.
+struct Shared {
+    path: PathBuf,
+    current: RwLock<Arc<Value>>,
+    text: Mutex<String>,
+    hooks: Mutex<Vec<ChangeHook>>,
+    pending: Mutex<Pending>,
+    stop: AtomicBool,
+}
+
+/// Toml file loaded as `Arc<toml::Value>` which can be swapped on reload.
+/// The polling thread, if any, is stopped when dropped.
+pub struct WatchedConfig {
+    shared: Arc<Shared>,
+    poller: Option<JoinHandle<()>>,
+}
+
+impl WatchedConfig {
+    /// Load the toml file, but not watch it yet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, WatchError> {
+        let path = path.as_ref().to_path_buf();
+        let text = read_file(&path)?;
+        let value = parse_text(&path, &text)?;
+        let shared = Shared {
+            path,
+            current: RwLock::new(Arc::new(value)),
+            text: Mutex::new(text),
+            hooks: Mutex::new(Vec::new()),
+            pending: Mutex::new(Pending::default()),
+            stop: AtomicBool::new(false),
+        };
+        Ok(Self { shared: Arc::new(shared), poller: None })
+    }
+
+    /// The file path being watched.
+    pub fn file(&self) -> &Path {
+        &self.shared.path
+    }
+
+    /// Get the current snapshot, it keeps unchanged even if reloaded later.
+    pub fn load(&self) -> Arc<Value> {
+        self.shared.current.read().unwrap().clone()
+    }
+
+    /// Register a callback to be called after each reload that change something.
+    /// No lock is held while calling it, so it may call `reload()` or `on_change()` again.
+    /// The hooks are called for one reload after another, so if a reload happens
+    /// while they are running, it is notified later by the same thread.
+    pub fn on_change<F>(&self, hook: F)
+    where F: Fn(&Value, &[String]) + Send + Sync + 'static
+    {
+        self.shared.hooks.lock().unwrap().push(Arc::new(hook));
+    }
+
+    /// Re-read the file now, return the changed paths, which is empty if not modified.
+    /// The current snapshot is kept if fail to read or parse the file.
+    pub fn reload(&self) -> Result<Vec<String>, WatchError> {
+        self.shared.reload()
+    }
+
+    /// Start a background thread to check the file every `interval`.
+    /// Errors in background reload are ignored, and the old snapshot is kept.
+    pub fn watch(&mut self, interval: Duration) {
+        if self.poller.is_some() {
+            return;
+        }
+        let shared = Arc::clone(&self.shared);
+        let poller = thread::spawn(move || {
+            loop {
+                thread::park_timeout(interval);
+                if shared.stop.load(Ordering::Acquire) {
+                    break;
+                }
+                let _ = shared.reload();
+            }
+        });
+        self.poller = Some(poller);
+    }
+}
+
+/// Stop the polling thread.
+impl Drop for WatchedConfig {
+    fn drop(&mut self) {
+        if let Some(poller) = self.poller.take() {
+            self.shared.stop.store(true, Ordering::Release);
+            poller.thread().unpark();
+            let _ = poller.join();
+        }
+    }
+}
+
+impl Shared {
+    fn reload(&self) -> Result<Vec<String>, WatchError> {
+        // read under the lock, or a slow reload could swap in older content
+        let mut last_text = self.text.lock().unwrap();
+        let text = read_file(&self.path)?;
+        if *last_text == text {
+            return Ok(Vec::new());
+        }
+        let value = Arc::new(parse_text(&self.path, &text)?);
+        *last_text = text;
+
+        let old = self.current.read().unwrap().clone();
+        let changed = changed_paths(&old, &value);
+        *self.current.write().unwrap() = Arc::clone(&value);
+        if !changed.is_empty() {
+            // queued under the text lock, so in the same order as the reloads
+            self.pending.lock().unwrap().queue.push_back((value, changed.clone()));
+        }
+        drop(last_text);
+
+        self.notify();
+        Ok(changed)
+    }
+
+    /// Call the hooks for the queued reloads without holding any lock,
+    /// unless another thread, or the hook calling reload, is already doing it.
+    fn notify(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.notifying {
+            return;
+        }
+        pending.notifying = true;
+        while let Some((value, changed)) = pending.queue.pop_front() {
+            drop(pending);
+            let hooks = self.hooks.lock().unwrap().clone();
+            for hook in hooks {
+                hook(&value, &changed);
+            }
+            pending = self.pending.lock().unwrap();
+        }
+        pending.notifying = false;
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, WatchError> {
+    std::fs::read_to_string(path).map_err(|e| WatchError::Io(path.to_path_buf(), e))
+}
+
+fn parse_text(path: &Path, text: &str) -> Result<Value, WatchError> {
+    text.parse().map_err(|e| WatchError::Parse(path.to_path_buf(), e))
+}
+
+/// Compare two toml trees and list the dotted paths that differ, in tree order.
+/// Added, removed and modified leaves are all reported,
+/// while a node that change type is reported as itself but not its children.
+pub fn changed_paths(old: &Value, new: &Value) -> Vec<String> {
+    let mut changed = Vec::new();
+    diff_value(old, new, "", &mut changed);
+    return changed;
+}
+
+fn diff_value(old: &Value, new: &Value, prefix: &str, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Table(old), Value::Table(new)) => {
+            for (k, v) in old {
+                let key = join_key(prefix, k);
+                match new.get(k) {
+                    Some(n) => diff_value(v, n, &key, changed),
+                    None => changed.push(key),
+                }
+            }
+            for k in new.keys() {
+                if !old.contains_key(k) {
+                    changed.push(join_key(prefix, k));
+                }
+            }
+        },
+        (Value::Array(old), Value::Array(new)) => {
+            for i in 0..old.len().max(new.len()) {
+                let key = join_key(prefix, &i.to_string());
+                match (old.get(i), new.get(i)) {
+                    (Some(o), Some(n)) => diff_value(o, n, &key, changed),
+                    _ => changed.push(key),
+                }
+            }
+        },
+        (old, new) => {
+            if old != new {
+                changed.push(prefix.to_string());
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,154 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use super::*;
+use crate::PathOperator;
+use std::sync::mpsc;
+use crate::tests::load_test_toml;
+
+/// Write toml text to a temp file unique to each test.
+fn temp_toml(name: &str, text: &str) -> PathBuf
+{
+    let path = std::env::temp_dir().join(format!("tomloper-{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    return path;
+}
+
+#[test]
+fn changed_paths_test() {
+    let old = load_test_toml();
+    let mut new = old.clone();
+    assert_eq!(changed_paths(&old, &new).is_empty(), true);
+
+    let _ = new.path_mut() / "host" / "port" << 9090;
+    let _ = new.path_mut() / "host" / "protocol" << ("quic",);
+    let _ = new.path_mut() / "misc" << ("new", 1);
+    let mut node = new.path_mut() / "service" / 0;
+    node <<= "flat";
+    assert_eq!(changed_paths(&old, &new),
+        vec!["host.port", "host.protocol.3", "misc.new", "service.0"]);
+    assert_eq!(changed_paths(&new, &old),
+        vec!["host.port", "host.protocol.3", "misc.new", "service.0"]);
+}
+
+#[test]
+fn reload_test() {
+    let path = temp_toml("reload", "[host]\nport = 8080\nip = \"127.0.0.1\"\n");
+    let config = WatchedConfig::open(&path).unwrap();
+    let (tx, rx) = mpsc::channel();
+    config.on_change(move |v, changed| {
+        let port = v.path() / "host" / "port" | 0;
+        tx.send((port, changed.to_vec())).unwrap();
+    });
+
+    let snapshot = config.load();
+    assert_eq!(config.reload().unwrap().is_empty(), true);
+
+    std::fs::write(&path, "[host]\nport = 9090\nip = \"127.0.0.1\"\n").unwrap();
+    assert_eq!(config.reload().unwrap(), vec!["host.port"]);
+    assert_eq!(rx.try_recv().unwrap(), (9090, vec!["host.port".to_string()]));
+    let port = config.load().path() / "host" / "port" | 0;
+    assert_eq!(port, 9090);
+    // old snapshot is not touched
+    let port = snapshot.path() / "host" / "port" | 0;
+    assert_eq!(port, 8080);
+
+    // keep the current value on bad file
+    std::fs::write(&path, "[host\nport = 7070\n").unwrap();
+    assert_eq!(matches!(config.reload(), Err(WatchError::Parse(_, _))), true);
+    let port = config.load().path() / "host" / "port" | 0;
+    assert_eq!(port, 9090);
+    assert_eq!(rx.try_recv().is_err(), true);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn watch_test() {
+    let path = temp_toml("watch", "port = 8080\n");
+    let mut config = WatchedConfig::open(&path).unwrap();
+    let (tx, rx) = mpsc::channel();
+    config.on_change(move |_, changed| {
+        tx.send(changed.to_vec()).unwrap();
+    });
+    config.watch(Duration::from_millis(10));
+
+    std::fs::write(&path, "port = 8081\n").unwrap();
+    let changed = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(changed, vec!["port"]);
+    let port = config.load().path() / "port" | 0;
+    assert_eq!(port, 8081);
+
+    drop(config);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn reentrant_hook_test() {
+    let path = temp_toml("reentrant", "port = 8080\n");
+    let config = Arc::new(WatchedConfig::open(&path).unwrap());
+    let (tx, rx) = mpsc::channel();
+    let weak = Arc::downgrade(&config);
+    config.on_change(move |_, changed| {
+        // would deadlock if the locks were held while calling hooks
+        let config = weak.upgrade().unwrap();
+        let again = config.reload().unwrap();
+        config.on_change(|_, _| {});
+        tx.send((changed.to_vec(), again)).unwrap();
+    });
+
+    std::fs::write(&path, "port = 8081\n").unwrap();
+    assert_eq!(config.reload().unwrap(), vec!["port"]);
+    assert_eq!(rx.try_recv().unwrap(), (vec!["port".to_string()], Vec::new()));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn notify_order_test() {
+    let path = temp_toml("notify-order", "n = 0\n");
+    let config = Arc::new(WatchedConfig::open(&path).unwrap());
+    let (tx, rx) = mpsc::channel();
+    let weak = Arc::downgrade(&config);
+    let file = path.clone();
+    config.on_change(move |v, _| {
+        let n = v.path() / "n" | 0;
+        tx.send(n).unwrap();
+        // reload from the hook is notified after this one
+        if n == 1 {
+            std::fs::write(&file, "n = 2\n").unwrap();
+            assert_eq!(weak.upgrade().unwrap().reload().unwrap(), vec!["n"]);
+        }
+    });
+    std::fs::write(&path, "n = 1\n").unwrap();
+    config.reload().unwrap();
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+
+    // concurrent reloads notify in the order of the file content
+    let writing = Arc::new(Mutex::new(2));
+    let threads: Vec<_> = (0..4).map(|_| {
+        let config = Arc::clone(&config);
+        let writing = Arc::clone(&writing);
+        let path = path.clone();
+        thread::spawn(move || {
+            for _ in 0..20 {
+                {
+                    let mut n = writing.lock().unwrap();
+                    *n += 1;
+                    // rename, so that no reload see a half written file
+                    let temp = path.with_extension("tmp");
+                    std::fs::write(&temp, format!("n = {}\n", n)).unwrap();
+                    std::fs::rename(&temp, &path).unwrap();
+                }
+                config.reload().unwrap();
+            }
+        })
+    }).collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+    let notified: Vec<i64> = rx.try_iter().collect();
+    assert_eq!(notified.windows(2).all(|w| w[0] < w[1]), true);
+    assert_eq!(notified.last(), Some(&82));
+
+    std::fs::remove_file(&path).unwrap();
+}